@@ -0,0 +1,101 @@
+use smallest_circle::circle::ClosedCircle;
+use smallest_circle::points::Point;
+use smallest_circle::polygon::ConvexPolygon;
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+
+// Multiset of points, keyed by their bit patterns
+#[derive(Debug, Default)]
+struct PointBag {
+	counts: HashMap<(u64, u64), usize>,
+}
+
+impl PointBag {
+	fn insert(&mut self, p: Point) {
+		*self.counts.entry(p.to_bits()).or_insert(0) += 1;
+	}
+
+	fn remove(&mut self, p: Point) -> bool {
+		let bits = p.to_bits();
+		match self.counts.get_mut(&bits) {
+			None => return false,
+			Some(1) => {self.counts.remove(&bits);},
+			Some(count) => {*count -= 1;},
+		};
+		true
+	}
+
+	fn distinct(&self) -> impl Iterator<Item=Point> + '_ {
+		self.counts.keys().map(|b| Point::from_bits(*b))
+	}
+}
+
+
+// The point cloud as it was before the hull backends: one incremental hull,
+// with every other point in a bag that popping a hull vertex rescans, and the
+// circle recomputed from the hull on every query. Kept to benchmark the
+// backends against.
+#[derive(Default)]
+pub struct MovingPointCloud {
+	cover: ConvexPolygon,
+	interior: PointBag,
+	point_log: VecDeque<Point>,
+}
+
+impl MovingPointCloud {
+	pub fn extend<I: Iterator<Item=Point>>(&mut self, iter: I) {
+		for p in iter {
+			self.push(p);
+		}
+	}
+
+	pub fn push(&mut self, p: Point) {
+		self.point_log.push_back(p);
+		for p in self.cover.try_insert(p).unwrap().into_iter() {
+			self.interior.insert(p);
+		}
+	}
+
+	pub fn pop(&mut self) {
+		if let Some(p) = self.point_log.pop_front() {
+			if self.interior.remove(p) {}
+
+			else if let Some(vertex) = self.cover.find(p) {
+				let mut lost_area = ConvexPolygon::new();
+				lost_area.try_insert(vertex.rev_vertex().position()).unwrap();
+				lost_area.try_insert(vertex.fwd_vertex().position()).unwrap();
+				lost_area.try_insert(p).unwrap();
+				let mut positions = self.cover.positions().to_vec();
+				positions.remove(vertex.to_id());
+				self.cover = ConvexPolygon::try_from_positions(positions).unwrap();
+
+				let mut move_to_cover = Vec::<Point>::new();
+
+				for p in self.interior.distinct()
+					.filter(|p| lost_area.covers(*p))
+				{
+					// A point promoted earlier in this loop can be obviated
+					// by a later one, and so returns to the interior
+					let move_to_interior = self.cover.try_insert(p).unwrap();
+					if !move_to_interior.contains(&p) {
+						move_to_cover.push(p);
+					}
+					move_to_cover.retain(|q| !move_to_interior.contains(q));
+				}
+				for p in move_to_cover.into_iter() {
+					self.interior.remove(p);
+				}
+			}
+
+			else {
+				unreachable!();
+			}
+		}
+	}
+
+	pub fn cover_circle(&self) -> ClosedCircle {
+		ClosedCircle::covering(self.cover.positions()).unwrap()
+	}
+}
@@ -0,0 +1,208 @@
+use smallest_circle::points::Point;
+use smallest_circle::smallest_cover::{Backend, MovingPointCloud};
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use std::time::{Duration, Instant};
+
+mod baseline;
+
+
+type Stream = fn(usize) -> Vec<Point>;
+
+
+// Deterministic xorshift64* stream of floats in [0, 1)
+struct Floats(u64);
+
+impl Iterator for Floats {
+	type Item = f64;
+
+	fn next(&mut self) -> Option<f64> {
+		self.0 ^= self.0 >> 12;
+		self.0 ^= self.0 << 25;
+		self.0 ^= self.0 >> 27;
+		let bits = self.0.wrapping_mul(0x2545_f491_4f6c_dd1d);
+		Some((bits >> 11) as f64 / (1_u64 << 53) as f64)
+	}
+}
+
+fn uniform(n: usize) -> Vec<Point> {
+	let mut floats = Floats(0x9e37_79b9_7f4a_7c15);
+	(0..n)
+		.map(|_| Point{x: floats.next().unwrap(), y: floats.next().unwrap()})
+		.collect()
+}
+
+// Standard normal, by the Box-Muller transform
+fn gaussian_pair(floats: &mut Floats) -> (f64, f64) {
+	let (u, v) = (1. - floats.next().unwrap(), floats.next().unwrap());
+	let (r, theta) = (f64::sqrt(-2. * u.ln()), v * std::f64::consts::TAU);
+	(r * theta.cos(), r * theta.sin())
+}
+
+fn gaussian(n: usize) -> Vec<Point> {
+	let mut floats = Floats(0x9e37_79b9_7f4a_7c15);
+	(0..n)
+		.map(|_| {
+			let (x, y) = gaussian_pair(&mut floats);
+			Point{x, y}
+		})
+		.collect()
+}
+
+// Tight clusters about a few uniformly placed centers
+fn clustered(n: usize) -> Vec<Point> {
+	let mut floats = Floats(0x9e37_79b9_7f4a_7c15);
+	let centers: Vec<Point> = (0..8)
+		.map(|_| Point{x: floats.next().unwrap(), y: floats.next().unwrap()})
+		.collect();
+	(0..n)
+		.map(|_| {
+			let center = centers[(floats.next().unwrap() * 8.) as usize];
+			let (dx, dy) = gaussian_pair(&mut floats);
+			Point{x: center.x + 0.01*dx, y: center.y + 0.01*dy}
+		})
+		.collect()
+}
+
+// Adversarial for hull-based covers: every point is a hull vertex
+fn on_circle(n: usize) -> Vec<Point> {
+	let mut floats = Floats(0x9e37_79b9_7f4a_7c15);
+	(0..n)
+		.map(|_| {
+			let theta = floats.next().unwrap() * std::f64::consts::TAU;
+			Point{x: theta.cos(), y: theta.sin()}
+		})
+		.collect()
+}
+
+// Degenerate: every point on the same line
+fn collinear(n: usize) -> Vec<Point> {
+	let mut floats = Floats(0x9e37_79b9_7f4a_7c15);
+	(0..n)
+		.map(|_| {
+			let t = floats.next().unwrap();
+			Point{x: t, y: t}
+		})
+		.collect()
+}
+
+// Each with the largest window to time operations on. With every point on
+// the hull, hull updates and circle recomputations take time linear in the
+// window, and filling it quadratic; so windows of points on a circle stop at
+// 10k, as filling a 1M window would take hours.
+const STREAMS: [(&str, Stream, usize); 5] = [
+	("uniform", uniform, 1_000_000),
+	("gaussian", gaussian, 1_000_000),
+	("clustered", clustered, 1_000_000),
+	("on_circle", on_circle, 10_000),
+	("collinear", collinear, 1_000_000),
+];
+
+const BACKENDS: [(&str, Backend); 2] = [("dynamic", Backend::Dynamic), ("fifo", Backend::Fifo)];
+
+// Slides a full window over the stream: one push, one pop and one query per
+// iteration, with each backend and with the cloud as it was before them
+fn sliding_window(c: &mut Criterion) {
+	for (stream_name, stream, _max_window) in STREAMS.iter() {
+		let mut group = c.benchmark_group(format!("sliding_window/{}", stream_name));
+		for window in [100, 1_000, 10_000].iter() {
+			let points = stream(4 * window);
+
+			for (backend_name, backend) in BACKENDS.iter() {
+				let mut cloud = MovingPointCloud::with_backend(*backend);
+				cloud.extend(points[..*window].iter().cloned());
+				let mut next = points.iter().cycle().skip(*window);
+
+				group.bench_function(BenchmarkId::new(*backend_name, window), |b| b.iter(|| {
+					cloud.push(*next.next().unwrap());
+					cloud.pop();
+					cloud.cover_circle()
+				}));
+			}
+
+			let mut cloud = baseline::MovingPointCloud::default();
+			cloud.extend(points[..*window].iter().cloned());
+			let mut next = points.iter().cycle().skip(*window);
+
+			group.bench_function(BenchmarkId::new("baseline", window), |b| b.iter(|| {
+				cloud.push(*next.next().unwrap());
+				cloud.pop();
+				cloud.cover_circle()
+			}));
+		}
+		group.finish();
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Operation {
+	Push,
+	Pop,
+	CoverCircle,
+}
+
+// Latency of each operation alone, on a full window kept at its size: each
+// iteration times one push, pop or query, and slides the window along
+// untimed. Each benchmark is named for its window size and the number of hull
+// vertices of the window once filled.
+fn operations(c: &mut Criterion) {
+	let operations = [
+		("push", Operation::Push),
+		("pop", Operation::Pop),
+		("cover_circle", Operation::CoverCircle),
+	];
+
+	for (stream_name, stream, max_window) in STREAMS.iter() {
+		// Filled once for all operations, as the largest take a while
+		let mut windows = Vec::new();
+		for window in [10, 100, 1_000, 10_000, 100_000, 1_000_000].iter().filter(|w| *w <= max_window) {
+			let points = stream(2 * window);
+			for (backend_name, backend) in BACKENDS.iter() {
+				let mut cloud = MovingPointCloud::with_backend(*backend);
+				cloud.extend(points[..*window].iter().cloned());
+				let parameter = format!("{}/hull_{}", window, cloud.hull_len());
+				windows.push((*window, *backend_name, parameter, cloud, points.clone()));
+			}
+		}
+
+		for (operation_name, operation) in operations.iter() {
+			let mut group = c.benchmark_group(format!("{}/{}", operation_name, stream_name));
+			for (window, backend_name, parameter, cloud, points) in windows.iter_mut() {
+				group.sample_size(if *window >= 100_000 {10} else {100});
+				let mut next = points.iter().cycle().skip(*window);
+
+				group.bench_function(BenchmarkId::new(*backend_name, &parameter), |b| b.iter_custom(|iters| {
+					let mut elapsed = Duration::ZERO;
+					for _ in 0..iters {
+						let p = *next.next().unwrap();
+						let start = Instant::now();
+						match operation {
+							Operation::Push => {
+								black_box(cloud.push(p));
+								elapsed += start.elapsed();
+								cloud.pop();
+							},
+							Operation::Pop => {
+								black_box(cloud.pop());
+								elapsed += start.elapsed();
+								cloud.push(p);
+							},
+							Operation::CoverCircle => {
+								black_box(cloud.cover_circle());
+								elapsed += start.elapsed();
+								cloud.push(p);
+								cloud.pop();
+							},
+						}
+					}
+					elapsed
+				}));
+			}
+			group.finish();
+		}
+	}
+}
+
+criterion_group!(benches, sliding_window, operations);
+criterion_main!(benches);
@@ -0,0 +1,104 @@
+// Drives both point cloud backends with random pushes, pops and removals,
+// checking them against each other and against brute force; run with
+// `cargo fuzz run moving_cloud`
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+
+use smallest_circle::circle::ClosedCircle;
+use smallest_circle::points::Point;
+use smallest_circle::smallest_cover::{Backend, MovingPointCloud};
+
+#[path = "../../tests/common/reference.rs"]
+mod reference;
+use reference::brute_force_sq_radius;
+
+
+// Largest window checked against the brute-force reference
+const MAX_WINDOW: usize = 12;
+
+#[derive(Debug, Arbitrary)]
+enum Op {
+	Push(Coords),
+	Pop,
+	// Retract the point at the given position, oldest first
+	Remove(u8),
+}
+
+// Coordinates chosen to hit degenerate cases often: a small grid gives
+// duplicates and collinear points, and points nudged off a circle by a few
+// ulps are nearly cocircular
+#[derive(Debug, Arbitrary)]
+enum Coords {
+	Grid(i8, i8),
+	Float(f64, f64),
+	OnCircle(u16, i8, i8),
+}
+
+impl Coords {
+	// `None` for coordinates large enough to overflow squared distances
+	fn point(&self) -> Option<Point> {
+		match *self {
+			Coords::Grid(x, y) => Some(Point{x: (x % 4) as f64, y: (y % 4) as f64}),
+			Coords::Float(x, y) => Some(Point{x, y}).filter(|_p| !(x.abs() > 1e100 || y.abs() > 1e100)),
+			Coords::OnCircle(degrees, dx, dy) => {
+				let theta = (degrees % 360) as f64 * std::f64::consts::PI / 180.;
+				let nudge = |c: f64, ulps: i8| c * (1. + (ulps % 4) as f64 * f64::EPSILON);
+				Some(Point{x: nudge(10. * theta.cos(), dx), y: nudge(10. * theta.sin(), dy)})
+			},
+		}
+	}
+}
+
+
+fuzz_target!(|ops: Vec<Op>| {
+	let mut dynamic = MovingPointCloud::with_backend(Backend::Dynamic);
+	let mut fifo = MovingPointCloud::with_backend(Backend::Fifo);
+
+	for op in ops.iter() {
+		match op {
+			Op::Push(coords) => {
+				let p = match coords.point() {
+					Some(p) => p,
+					None => continue,
+				};
+				assert_eq!(dynamic.try_push(p).is_ok(), p.is_finite());
+				assert_eq!(fifo.try_push(p).is_ok(), p.is_finite());
+				if dynamic.len() > MAX_WINDOW {
+					assert_eq!(dynamic.pop(), fifo.pop());
+				}
+			},
+			Op::Pop => {
+				assert_eq!(dynamic.pop(), fifo.pop());
+			},
+			Op::Remove(index) => {
+				let handle = dynamic.iter().map(|(h, _p)| h)
+					.nth(*index as usize % dynamic.len().max(1));
+				if let Some(handle) = handle {
+					assert_eq!(dynamic.remove(handle), fifo.remove(handle));
+				}
+			},
+		}
+		check(&dynamic, &fifo);
+	}
+});
+
+fn check(dynamic: &MovingPointCloud, fifo: &MovingPointCloud) {
+	assert_eq!(dynamic.validate(), Ok(()));
+	assert_eq!(fifo.validate(), Ok(()));
+
+	let points: Vec<Point> = dynamic.iter().map(|(_h, p)| p).collect();
+	let circle = match dynamic.checked_cover_circle() {
+		Some(circle) => circle,
+		None => return,
+	};
+	assert_eq!(Some(circle), fifo.checked_cover_circle());
+	assert_eq!(Some(circle), ClosedCircle::covering(&points));
+	assert!(points.iter().all(|p| circle.covers(*p)));
+
+	// The reference allows itself some rounding error, and so may come out
+	// slightly smaller
+	let best = brute_force_sq_radius(&points);
+	assert!(circle.sq_radius <= best * (1. + 1e-8) + 1e-12, "{} > {}", circle.sq_radius, best);
+}
@@ -0,0 +1,335 @@
+use crate::points::{Point, Point3};
+use crate::circle::{ClosedCircle, shuffled, least_sq_radius};
+use crate::predicates::ball_excess;
+
+use std::cmp::Ordering;
+use std::fmt;
+
+
+// Point in any fixed number of dimensions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointN<const D: usize>(pub [f64; D]);
+
+// Ball of all points within the radius of the center, boundary included
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClosedBall<const D: usize> {
+	pub center: PointN<D>,
+	pub sq_radius: f64,
+}
+
+
+impl<const D: usize> PointN<D> {
+	pub fn is_finite(&self) -> bool {
+		self.0.iter().all(|c| c.is_finite())
+	}
+
+	fn sq_dist(&self, other: &Self) -> f64 {
+		self.0.iter().zip(other.0.iter()).map(|(a, b)| (a-b) * (a-b)).sum()
+	}
+}
+
+impl<const D: usize> fmt::Display for PointN<D> {
+	fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(formatter, "(")?;
+		for (i, c) in self.0.iter().enumerate() {
+			if i > 0 {
+				write!(formatter, ", ")?;
+			}
+			write!(formatter, "{}", c)?;
+		}
+		write!(formatter, ")")
+	}
+}
+
+// Serialized as a sequence of coordinates, as serde's derive does not cover
+// arrays of generic length
+#[cfg(feature = "serde")]
+impl<const D: usize> serde::Serialize for PointN<D> {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_seq(self.0.iter())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const D: usize> serde::Deserialize<'de> for PointN<D> {
+	fn deserialize<De: serde::Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+		let coords: Vec<f64> = serde::Deserialize::deserialize(deserializer)?;
+		let len = coords.len();
+		std::convert::TryInto::try_into(coords)
+			.map(Self)
+			.map_err(|_| serde::de::Error::invalid_length(len, &&*format!("{} coordinates", D)))
+	}
+}
+
+impl From<Point> for PointN<2> {
+	fn from(p: Point) -> Self {
+		Self([p.x, p.y])
+	}
+}
+
+impl From<PointN<2>> for Point {
+	fn from(p: PointN<2>) -> Self {
+		Self{x: p.0[0], y: p.0[1]}
+	}
+}
+
+impl From<Point3> for PointN<3> {
+	fn from(p: Point3) -> Self {
+		Self([p.x, p.y, p.z])
+	}
+}
+
+impl From<PointN<3>> for Point3 {
+	fn from(p: PointN<3>) -> Self {
+		Self{x: p.0[0], y: p.0[1], z: p.0[2]}
+	}
+}
+
+
+impl<const D: usize> ClosedBall<D> {
+	pub fn new(center: PointN<D>, radius: f64) -> Self {
+		Self {center, sq_radius: radius*radius}
+	}
+
+	pub fn from_point(p: PointN<D>) -> Self {
+		Self {center: p, sq_radius: 0.}
+	}
+
+	// Smallest ball with all the points on its boundary, centered in their
+	// affine hull; `None` if the points are affinely dependent (e.g. three
+	// collinear points), or there are none
+	pub fn through_points(points: &[PointN<D>]) -> Option<Self> {
+		let (origin, others) = points.split_first()?;
+		let vectors: Vec<[f64; D]> = others.iter()
+			.map(|p| {
+				let mut v = p.0;
+				v.iter_mut().zip(origin.0.iter()).for_each(|(c, o)| *c -= o);
+				v
+			})
+			.collect();
+		let dot = |v1: &[f64; D], v2: &[f64; D]| -> f64 {
+			v1.iter().zip(v2.iter()).map(|(a, b)| a*b).sum()
+		};
+
+		// The center is the origin plus a combination of the vectors, whose
+		// weights solve the (Gram) system 2 vⱼ·Σᵢ wᵢvᵢ = vⱼ·vⱼ
+		let mut system: Vec<Vec<f64>> = vectors.iter()
+			.map(|vj| {
+				let mut row: Vec<f64> = vectors.iter().map(|vi| 2. * dot(vj, vi)).collect();
+				row.push(dot(vj, vj));
+				row
+			})
+			.collect();
+		let weights = solve(&mut system)?;
+
+		let mut center = origin.0;
+		for (w, v) in weights.iter().zip(vectors.iter()) {
+			center.iter_mut().zip(v.iter()).for_each(|(c, x)| *c += w * x);
+		}
+		let center = PointN(center);
+		if !center.is_finite() {
+			return None;
+		}
+		Some(Self {center, sq_radius: center.sq_dist(origin)})
+	}
+
+	// Decided exactly, for the ball as stored
+	pub fn covers(&self, point: &PointN<D>) -> bool {
+		ball_excess(&self.center.0, self.sq_radius, &point.0) <= 0.
+	}
+
+	pub fn radius(&self) -> f64 {
+		f64::sqrt(self.sq_radius)
+	}
+
+	// Smallest ball covering every point, via Welzl's algorithm with Gärtner's
+	// move-to-front heuristic, over a fixed pseudo-random permutation of the
+	// input. The result covers every point exactly.
+	//
+	// In two dimensions, this is `ClosedCircle::covering`, bit for bit, so
+	// the support points are found exactly as well. In others it is not
+	// exact: support points are chosen against balls computed in floating
+	// point, and nearly dependent ones are taken for dependent (see `solve`),
+	// so in degenerate cases the result may be larger than it need be by a
+	// rounding error.
+	pub fn covering(points: &[PointN<D>]) -> Option<Self> {
+		if D == 2 {
+			let points: Vec<Point> = points.iter().map(|p| Point{x: p.0[0], y: p.0[1]}).collect();
+			let circle = ClosedCircle::covering(&points)?;
+			let mut center = [0.; D];
+			center[..2].copy_from_slice(&[circle.center.x, circle.center.y]);
+			return Some(Self {center: PointN(center), sq_radius: circle.sq_radius});
+		}
+
+		let mut order = shuffled(points);
+		let mut support = Vec::with_capacity(D + 1);
+		let mut ball = move_to_front(&mut order, points.len(), &mut support)?;
+
+		// Absorb any rounding error, so that `covers` holds for every point
+		let center = ball.center;
+		for p in points.iter() {
+			if !ball.covers(p) {
+				ball.sq_radius = ball.sq_radius.max(sq_radius_reaching(&center, p));
+			}
+		}
+
+		Some(ball)
+	}
+
+	fn from_support(support: &[PointN<D>]) -> Option<Self> {
+		Self::through_points(support).or_else(|| {
+			// Affinely dependent, which in exact arithmetic would not arise;
+			// reach the last point from the ball through the others
+			let (last, others) = support.split_last()?;
+			let mut ball = Self::from_support(others)?;
+			ball.sq_radius = ball.sq_radius.max(sq_radius_reaching(&ball.center, last));
+			Some(ball)
+		})
+	}
+}
+
+
+// Smallest ball covering the first `n` points with the support points on its
+// boundary, moving each point found outside it to the front
+fn move_to_front<const D: usize>(
+	points: &mut [PointN<D>],
+	n: usize,
+	support: &mut Vec<PointN<D>>,
+) -> Option<ClosedBall<D>> {
+	let mut ball = ClosedBall::from_support(support);
+	if support.len() == D + 1 {
+		return ball;
+	}
+
+	for i in 0..n {
+		let p = points[i];
+		if ball.is_some_and(|b| b.covers(&p)) {
+			continue;
+		}
+		support.push(p);
+		ball = move_to_front(points, i, support);
+		support.pop();
+		points[..=i].rotate_right(1);
+	}
+
+	ball
+}
+
+// Pivots at most this fraction of the largest coefficient are taken for zero
+const PIVOT_TOLERANCE: f64 = 1e-12;
+
+// Solves a linear system, given as rows of coefficients followed by the
+// right-hand side, by Gaussian elimination with partial pivoting; `None` if
+// it is singular, or nearly so, as rounding error can leave a singular system
+// with tiny pivots that would give a wild solution
+fn solve(system: &mut [Vec<f64>]) -> Option<Vec<f64>> {
+	let n = system.len();
+	let scale = system.iter()
+		.flat_map(|row| row[..n].iter())
+		.fold(0., |scale: f64, x| scale.max(x.abs()));
+	for col in 0..n {
+		let pivot = (col..n)
+			.max_by(|i, j| {
+				system[*i][col].abs().partial_cmp(&system[*j][col].abs())
+					.unwrap_or(Ordering::Equal)
+			})?;
+		if system[pivot][col].abs() <= PIVOT_TOLERANCE * scale || !system[pivot][col].is_finite() {
+			return None;
+		}
+		system.swap(col, pivot);
+
+		let (upper, lower) = system.split_at_mut(col+1);
+		let pivot_row = &upper[col];
+		for row in lower.iter_mut() {
+			let factor = row[col] / pivot_row[col];
+			row[col..].iter_mut().zip(pivot_row[col..].iter()).for_each(|(x, p)| *x -= factor * p);
+		}
+	}
+
+	let mut solution = vec![0.; n];
+	for row in (0..n).rev() {
+		let sum: f64 = (row+1..n).map(|k| system[row][k] * solution[k]).sum();
+		solution[row] = (system[row][n] - sum) / system[row][row];
+	}
+	Some(solution)
+}
+
+// Smallest squared radius of a ball about `center` that covers `p`
+fn sq_radius_reaching<const D: usize>(center: &PointN<D>, p: &PointN<D>) -> f64 {
+	least_sq_radius(center.sq_dist(p), |sq_radius| ball_excess(&center.0, sq_radius, &p.0).partial_cmp(&0.))
+		.unwrap_or(f64::INFINITY)
+}
+
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+	use super::{ClosedBall, PointN};
+	use crate::points::Point;
+	use crate::circle::ClosedCircle;
+
+	#[test]
+	fn through_points() {
+		let ball = ClosedBall::through_points(&[PointN([2., 0., 0.]), PointN([0., 2., 0.])]);
+		assert_eq!(ball, Some(ClosedBall{center: PointN([1., 1., 0.]), sq_radius: 2.}));
+
+		// Right angle at the origin, so the hypotenuse is a diameter
+		let ball = ClosedBall::through_points(&[
+			PointN([0., 0., 0., 1.]), PointN([2., 0., 0., 1.]), PointN([0., 2., 0., 1.]),
+		]);
+		assert_eq!(ball, Some(ClosedBall{center: PointN([1., 1., 0., 1.]), sq_radius: 2.}));
+
+		let collinear = [PointN([0., 0.]), PointN([1., 1.]), PointN([2., 2.])];
+		assert_eq!(ClosedBall::through_points(&collinear), None);
+		// Collinear but for a rounding error, which leaves a tiny pivot
+		let z = f64::from_bits(2_f64.to_bits() + 1);
+		let nearly_collinear = [PointN([0., 0., 0.]), PointN([1., 1., 1.]), PointN([2., 2., z])];
+		assert_eq!(ClosedBall::through_points(&nearly_collinear), None);
+		assert_eq!(ClosedBall::<3>::through_points(&[]), None);
+	}
+
+	#[test]
+	fn covering() {
+		assert_eq!(ClosedBall::<4>::covering(&[]), None);
+
+		// Cross-polytope, with interior points
+		let mut points = Vec::new();
+		for axis in 0..4 {
+			for sign in [-1., 1.].iter() {
+				let mut p = [0.; 4];
+				p[axis] = *sign;
+				points.push(PointN(p));
+			}
+		}
+		points.push(PointN([0.5, 0.5, 0.5, 0.]));
+		points.push(PointN([0.; 4]));
+		let ball = ClosedBall::covering(&points).unwrap();
+		assert!(points.iter().all(|p| ball.covers(p)));
+		assert!((ball.radius() - 1.).abs() < 1e-12);
+
+		// Collinear points
+		let points = [PointN([0., 0., 0.]), PointN([3., 3., 3.]), PointN([1., 1., 1.])];
+		let ball = ClosedBall::covering(&points).unwrap();
+		assert_eq!(ball.center, PointN([1.5, 1.5, 1.5]));
+		assert!(points.iter().all(|p| ball.covers(p)));
+	}
+
+	#[test]
+	fn covering_plane() {
+		let points: Vec<Point> = (0..50_u64)
+			.map(|i| (i*37 + 11) % 101)
+			.map(|i| Point{x: (i as f64 * 0.37).sin() * 10., y: (i as f64 * 0.91).cos() * 3.})
+			.collect();
+		let circle = ClosedCircle::covering(&points).unwrap();
+		let ball = ClosedBall::covering(&points.iter().map(|p| PointN::from(*p)).collect::<Vec<_>>()).unwrap();
+		assert_eq!(Point::from(ball.center), circle.center);
+		assert_eq!(ball.sq_radius, circle.sq_radius);
+	}
+
+	#[test]
+	fn display() {
+		assert_eq!(PointN([1., -2.5, 3.]).to_string(), "(1, -2.5, 3)");
+	}
+}
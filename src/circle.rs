@@ -1,54 +1,430 @@
-use crate::points::Point;
+use crate::points::{Point, cmp_lexicographic};
+use crate::scalar::Scalar;
+use crate::polygon::ConvexPolygon;
 
+use std::cmp::Ordering;
 
-#[derive(Debug, PartialEq)]
-pub struct ClosedCircle {
-	pub center: Point,
-	pub sq_radius: f64,
+
+// Disc of all points within the radius of the center, boundary included
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClosedCircle<T = f64> {
+	pub center: Point<T>,
+	pub sq_radius: T,
 }
 
 
-impl ClosedCircle {
-	pub fn from_two_points(p1: Point, p2: Point) -> Self {
-		Self {
-			center: p1 + (p2-p1)*0.5,
-			sq_radius: 0.25*(p2-p1).sq_mag()
+// Most outliers for which `covering_all_but_counted` searches exhaustively,
+// trying up to 3^k sets of them
+pub(crate) const EXACT_OUTLIERS: usize = 4;
+
+// Smallest circle covering some points, `None` if it overflows the scalar
+// type, with the support points it is built from
+pub(crate) type Covering<T> = (Option<ClosedCircle<T>>, Vec<Point<T>>);
+
+
+// Circles through given points are computed in f64, and rounded to the scalar
+// type; so unless the scalar is f64, they need not pass through those points
+impl<T: Scalar> ClosedCircle<T> {
+	pub fn new(center: Point<T>, radius: T) -> Self {
+		Self {center, sq_radius: radius*radius}
+	}
+
+	pub fn from_point(p: Point<T>) -> Self {
+		Self {center: p, sq_radius: T::default()}
+	}
+
+	pub fn from_two_points(p1: Point<T>, p2: Point<T>) -> Self {
+		let (p1, p2) = (p1.to_f64(), p2.to_f64());
+		Self::from_f64(p1 + (p2-p1)*0.5, 0.25*(p2-p1).sq_mag())
+	}
+
+	pub fn from_three_points(p1: Point<T>, p2: Point<T>, p3: Point<T>) -> Self {
+		let (p1, p2, p3) = (p1.to_f64(), p2.to_f64(), p3.to_f64());
+		let center = circumcenter(p1, p2, p3);
+		Self::from_f64(center, (p1-center).sq_mag())
+	}
+
+	// Fails if the points are collinear (or coincide), as decided exactly, in
+	// which case no finite circle passes through all three; or if the
+	// circumcenter is too far off to represent
+	pub fn try_from_three_points(p1: Point<T>, p2: Point<T>, p3: Point<T>) -> Option<Self> {
+		if T::orient2d(p1, p2, p3).unwrap_or(Ordering::Equal) == Ordering::Equal {
+			return None;
+		}
+		let (p1, p2, p3) = (p1.to_f64(), p2.to_f64(), p3.to_f64());
+		let center = circumcenter(p1, p2, p3);
+		if center.x.is_finite() && center.y.is_finite() {
+			Some(Self::from_f64(center, (p1-center).sq_mag()))
+		} else {
+			None
 		}
 	}
 
-	pub fn from_three_points(p1: Point, p2: Point, p3: Point) -> Self {
-		// see http://ambrsoft.com/TrigoCalc/Circle3D.htm
-		let origin = Point::default();
-		let denominator = 2. * (
-			p1.x * (p2.y-p3.y)
-			- p1.y * (p2.x-p3.x)
-			+ p2.x*p3.y
-			- p2.y*p3.x
-		);
-		let center = Point{
-			x: (
-				(p1-origin).sq_mag() * (p2.y-p3.y)
-				+ (p2-origin).sq_mag() * (p3.y-p1.y)
-				+ (p3-origin).sq_mag() * (p1.y-p2.y)
-			) / denominator,
-			y: (
-				(p1-origin).sq_mag() * (p3.x-p2.x)
-				+ (p2-origin).sq_mag() * (p1.x-p3.x)
-				+ (p3-origin).sq_mag() * (p2.x-p1.x)
-			) / denominator,
-		};
-		
-		Self {center, sq_radius: (p1-center).sq_mag()}
+	// Falls back to the circle whose diameter spans the two farthest-apart
+	// points, which covers the third when all three are collinear
+	pub fn from_three_points_or_diameter(p1: Point<T>, p2: Point<T>, p3: Point<T>) -> Self {
+		Self::try_from_three_points(p1, p2, p3).unwrap_or_else(|| {
+			let (q1, q2) = farthest_pair(p1, p2, p3);
+			Self::from_two_points(q1, q2)
+		})
+	}
 
+	fn from_f64(center: Point, sq_radius: f64) -> Self {
+		Self {center: Point::from_f64(center), sq_radius: T::from_f64(sq_radius)}
 	}
 
-	pub fn covers(&self, point: Point) -> bool {
-		(point - self.center).sq_mag() <= self.sq_radius
+	// Decided exactly, for the circle as stored
+	pub fn covers(&self, point: Point<T>) -> bool {
+		T::circle_excess(self.center, self.sq_radius, point) != Some(Ordering::Greater)
 	}
 
 	pub fn radius(&self) -> f64 {
-		f64::sqrt(self.sq_radius)
+		f64::sqrt(self.sq_radius.to_f64())
+	}
+
+	// Smallest circle covering every point (see `support`); for any scalar
+	// type, the result covers every point exactly, and depends only on the
+	// set of positions. `None` if there are no points, or if the squared
+	// radius overflows the scalar type.
+	pub fn covering(points: &[Point<T>]) -> Option<Self> {
+		Self::covering_with_support(points).and_then(|(circle, _support)| circle)
+	}
+
+	// As `covering`, along with the support points the circle is built from;
+	// `None` if there are no points, and the circle `None` if it overflows
+	pub(crate) fn covering_with_support(points: &[Point<T>]) -> Option<Covering<T>> {
+		let support = support(points)?;
+		let mut circle = Self::from_support(&support);
+
+		// Absorb any rounding error, so that `covers` holds for every point
+		// (by as little as possible, so that points inside the hull of the
+		// others never change the result)
+		let center = circle.center;
+		for p in points.iter() {
+			if !circle.covers(*p) {
+				match sq_radius_reaching(center, *p) {
+					Some(sq_radius) if sq_radius > circle.sq_radius => circle.sq_radius = sq_radius,
+					Some(_) => {},
+					None => return Some((None, support)),
+				}
+			}
+		}
+
+		Some((Some(circle).filter(|circle| circle.sq_radius.is_finite()), support))
+	}
+
+	// Smallest circle covering all but at most `k` of the points, along with
+	// the positions it leaves out, in lexicographic order; leaving out a
+	// position leaves out every point there, and at least one point is always
+	// kept. Exact for up to `EXACT_OUTLIERS` outliers; beyond that, found
+	// greedily, by leaving out the support point that shrinks the circle the
+	// most for as long as any does. Takes O(k n log n) time to find the
+	// candidate outliers, and then O(3^k m) or O(k m) for m candidates.
+	//
+	// A smaller circle than the one covering every point must leave out one
+	// of its support points, so trying each in turn finds the smallest.
+	//
+	// Takes the vertices of the hull of the points, and their distinct
+	// positions, in lexicographic order, each with its number of points;
+	// peeling layers starts from the given hull, and needs only k more.
+	pub(crate) fn covering_all_but_counted(
+		hull: &[Point<T>],
+		counted: Candidates<T>,
+		k: usize,
+	) -> Option<(Self, Vec<Point<T>>)> {
+		let candidates = outer_layers(hull, counted, k);
+		let mut outliers = Vec::new();
+		if k <= EXACT_OUTLIERS {
+			let mut best = None;
+			search(&candidates, k, &mut outliers, &mut best);
+			return best.map(|(circle, mut outliers)| {
+				outliers.sort_by(cmp_lexicographic);
+				(circle, outliers)
+			});
+		}
+
+		let mut candidates = candidates;
+		let mut budget = k;
+		let (circle, mut support) = Self::covering_with_support(&positions(&candidates))?;
+		let mut circle = circle?;
+		loop {
+			let smallest = support.iter()
+				.filter_map(|s| {
+					let (rest, count) = without(&candidates, *s).filter(|(_rest, count)| *count <= budget)?;
+					let (circle, support) = Self::covering_with_support(&positions(&rest))?;
+					Some((*s, rest, count, circle?, support))
+				})
+				.min_by(|c1, c2| c1.3.sq_radius.partial_cmp(&c2.3.sq_radius).unwrap_or(Ordering::Equal));
+			match smallest {
+				Some((s, rest, count, smaller, smaller_support)) if smaller.sq_radius < circle.sq_radius => {
+					outliers.push(s);
+					candidates = rest;
+					budget -= count;
+					circle = smaller;
+					support = smaller_support;
+				},
+				_ => break,
+			}
+		}
+		outliers.sort_by(cmp_lexicographic);
+		Some((circle, outliers))
+	}
+
+	// As `covering_all_but_counted`, for points in any order
+	#[cfg(test)]
+	pub(crate) fn covering_all_but(points: &[Point<T>], k: usize) -> Option<(Self, Vec<Point<T>>)> {
+		let mut sorted = points.to_vec();
+		sorted.sort_by(cmp_lexicographic);
+		let mut counted: Candidates<T> = Vec::new();
+		for p in sorted {
+			match counted.last_mut() {
+				Some((q, count)) if *q == p => *count += 1,
+				_ => counted.push((p, 1)),
+			}
+		}
+		Self::covering_all_but_counted(ConvexPolygon::hull_of(points).positions(), counted, k)
+	}
+
+	// Circle through the support points, before absorbing rounding error
+	pub(crate) fn from_support(support: &[Point<T>]) -> Self {
+		match *support {
+			[p1] => Self::from_point(p1),
+			[p1, p2] => Self::from_two_points(p1, p2),
+			[p1, p2, p3] => Self::from_three_points_or_diameter(p1, p2, p3),
+			_ => unreachable!(),
+		}
+	}
+}
+
+
+// see http://ambrsoft.com/TrigoCalc/Circle3D.htm
+fn circumcenter(p1: Point, p2: Point, p3: Point) -> Point {
+	let origin = Point::default();
+	let denominator = 2. * (
+		p1.x * (p2.y-p3.y)
+		- p1.y * (p2.x-p3.x)
+		+ p2.x*p3.y
+		- p2.y*p3.x
+	);
+	Point{
+		x: (
+			(p1-origin).sq_mag() * (p2.y-p3.y)
+			+ (p2-origin).sq_mag() * (p3.y-p1.y)
+			+ (p3-origin).sq_mag() * (p1.y-p2.y)
+		) / denominator,
+		y: (
+			(p1-origin).sq_mag() * (p3.x-p2.x)
+			+ (p2-origin).sq_mag() * (p1.x-p3.x)
+			+ (p3-origin).sq_mag() * (p2.x-p1.x)
+		) / denominator,
+	}
+}
+
+
+// Points on the boundary of the smallest circle covering all the points, in
+// lexicographic order; found by Welzl's algorithm (iterative form, over a
+// fixed pseudo-random permutation of the vertices of their convex hull),
+// deciding coverage exactly. Every point on the circle is a hull vertex; of
+// more than three, the lexicographically smallest three are kept, so that the
+// support, and the circle built from it, depend only on the set of positions.
+pub(crate) fn support<T: Scalar>(points: &[Point<T>]) -> Option<Vec<Point<T>>> {
+	let order = shuffled(ConvexPolygon::hull_of(points).positions());
+	let mut support = vec![*order.first()?];
+
+	for i in 1..order.len() {
+		if support_covers(&support, order[i]) {
+			continue;
+		}
+		support = vec![order[i]];
+		for j in 0..i {
+			if support_covers(&support, order[j]) {
+				continue;
+			}
+			support = vec![order[i], order[j]];
+			for k in 0..j {
+				if support_covers(&support, order[k]) {
+					continue;
+				}
+				support = vec![order[i], order[j], order[k]];
+			}
+		}
+	}
+
+	// Any three points on the circle determine it, whichever the permutation
+	// happened to find
+	let mut boundary: Vec<Point<T>> = order.iter().copied()
+		.filter(|p| support_side(&support, *p) == Some(Ordering::Equal))
+		.collect();
+	boundary.sort_by(cmp_lexicographic);
+	boundary.truncate(3);
+	Some(boundary)
+}
+
+// Whether the smallest circle through all the support points covers `point`,
+// decided exactly
+fn support_covers<T: Scalar>(support: &[Point<T>], point: Point<T>) -> bool {
+	support_side(support, point) != Some(Ordering::Greater)
+}
+
+// Whether `point` lies within (`Less`), on (`Equal`) or beyond (`Greater`)
+// the smallest circle through all the support points, decided exactly
+pub(crate) fn support_side<T: Scalar>(support: &[Point<T>], point: Point<T>) -> Option<Ordering> {
+	match *support {
+		[p1] => Some(if point == p1 {Ordering::Equal} else {Ordering::Greater}),
+		// Thales: the angle the diameter subtends at the point is obtuse,
+		// right or acute
+		[p1, p2] => T::dot2d(point, p1, p2),
+		[p1, p2, p3] => match T::orient2d(p1, p2, p3)? {
+			Ordering::Equal => {
+				let (q1, q2) = farthest_pair(p1, p2, p3);
+				support_side(&[q1, q2], point)
+			},
+			orientation => T::incircle(p1, p2, p3, point).map(|side| match side {
+				Ordering::Equal => Ordering::Equal,
+				side if side == orientation => Ordering::Less,
+				_ => Ordering::Greater,
+			}),
+		},
+		_ => unreachable!(),
+	}
+}
+
+// Of three collinear points, the two farthest apart: the ends of the segment
+// that the third lies on, decided exactly
+pub(crate) fn farthest_pair<T: Scalar>(p1: Point<T>, p2: Point<T>, p3: Point<T>) -> (Point<T>, Point<T>) {
+	farthest_pair_by(p1, p2, p3, |p, q1, q2| T::dot2d(p, q1, q2) != Some(Ordering::Greater))
+}
+
+// As `farthest_pair`, in any dimension, given whether a point lies between
+// two others (i.e. at no acute angle to them)
+pub(crate) fn farthest_pair_by<P: Copy, F>(p1: P, p2: P, p3: P, between: F) -> (P, P)
+where
+	F: Fn(P, P, P) -> bool,
+{
+	if between(p3, p1, p2) {
+		(p1, p2)
+	} else if between(p1, p2, p3) {
+		(p2, p3)
+	} else {
+		(p3, p1)
+	}
+}
+
+// Distinct positions, each with its number of points
+pub(crate) type Candidates<T> = Vec<(Point<T>, usize)>;
+
+// Positions of the points on the outermost `k + 1` convex layers (each the
+// vertices of the hull of the points within the last). Once at most `k`
+// positions are left out, one of these layers is whole, and every point
+// deeper in lies within its hull; so only these points can be support points,
+// and the circle covering them covers the rest. Takes the outermost layer as
+// given, and the positions in lexicographic order.
+fn outer_layers<T: Scalar>(hull: &[Point<T>], counted: Candidates<T>, k: usize) -> Candidates<T> {
+	let mut rest = counted;
+	let mut layers = Vec::new();
+	let mut hull = hull.to_vec();
+	for depth in 0..=k {
+		if rest.is_empty() {
+			break;
+		}
+		if depth > 0 {
+			hull = ConvexPolygon::hull_of(&positions(&rest)).positions().to_vec();
+		}
+		hull.sort_by(cmp_lexicographic);
+		let (layer, inner): (Vec<_>, Vec<_>) = rest.into_iter()
+			.partition(|(p, _count)| hull.binary_search_by(|q| cmp_lexicographic(q, p)).is_ok());
+		layers.extend(layer);
+		rest = inner;
+	}
+	layers
+}
+
+// Tries leaving out each support point of the circle covering the candidates
+// in turn, and then each of the next circle's, and so on, while `k` points
+// may still be left out; keeps the smallest circle found, and the positions
+// it leaves out
+fn search<T: Scalar>(
+	candidates: &[(Point<T>, usize)],
+	k: usize,
+	outliers: &mut Vec<Point<T>>,
+	best: &mut Option<(ClosedCircle<T>, Vec<Point<T>>)>,
+) {
+	let (circle, support) = match ClosedCircle::covering_with_support(&positions(candidates)) {
+		Some(covering) => covering,
+		None => return,
+	};
+	// Leaving out more points may yet bring an overflowing circle in range
+	if let Some(circle) = circle {
+		if best.as_ref().is_none_or(|(best, _outliers)| circle.sq_radius < best.sq_radius) {
+			*best = Some((circle, outliers.clone()));
+		}
+	}
+
+	for s in support {
+		if let Some((rest, count)) = without(candidates, s).filter(|(_rest, count)| *count <= k) {
+			outliers.push(s);
+			search(&rest, k - count, outliers, best);
+			outliers.pop();
+		}
+	}
+}
+
+// Candidates other than the given position, and the number of points there;
+// `None` if it is the only one
+fn without<T: Scalar>(candidates: &[(Point<T>, usize)], p: Point<T>) -> Option<(Candidates<T>, usize)> {
+	if candidates.len() <= 1 {
+		return None;
+	}
+	let count = candidates.iter().find(|(q, _count)| *q == p)?.1;
+	Some((candidates.iter().filter(|(q, _count)| *q != p).cloned().collect(), count))
+}
+
+fn positions<T: Scalar>(candidates: &[(Point<T>, usize)]) -> Vec<Point<T>> {
+	candidates.iter().map(|(p, _count)| *p).collect()
+}
+
+// Smallest squared radius of a circle about `center` that covers `p`; `None`
+// if even the largest scalar falls short
+fn sq_radius_reaching<T: Scalar>(center: Point<T>, p: Point<T>) -> Option<T> {
+	least_sq_radius(T::sq_distance(center, p)?, |sq_radius| T::circle_excess(center, sq_radius, p))
+}
+
+// Smallest squared radius at which a point lies on or within a ball, found by
+// stepping through adjacent scalars from an estimate, given the point's
+// excess over the ball of each squared radius; `None` if even the largest
+// scalar falls short
+pub(crate) fn least_sq_radius<T: Scalar, F>(estimate: T, excess: F) -> Option<T>
+where
+	F: Fn(T) -> Option<Ordering>,
+{
+	let mut sq_radius = estimate;
+	while sq_radius > T::default() && excess(sq_radius.next_down()) != Some(Ordering::Greater) {
+		sq_radius = sq_radius.next_down();
+	}
+	while excess(sq_radius) == Some(Ordering::Greater) {
+		if sq_radius.next_up() == sq_radius {
+			return None;
+		}
+		sq_radius = sq_radius.next_up();
+	}
+	Some(sq_radius)
+}
+
+pub(crate) fn shuffled<P: Copy>(points: &[P]) -> Vec<P> {
+	// xorshift64*, with a fixed seed to keep results reproducible
+	let mut state = 0x2545_f491_4f6c_dd1d_u64;
+	let mut order = points.to_vec();
+
+	for i in (1..order.len()).rev() {
+		state ^= state >> 12;
+		state ^= state << 25;
+		state ^= state >> 27;
+		let j = (state.wrapping_mul(0x2545_f491_4f6c_dd1d) % (i as u64 + 1)) as usize;
+		order.swap(i, j);
 	}
+
+	order
 }
 
 
@@ -58,6 +434,14 @@ impl ClosedCircle {
 mod tests {
 	use super::{ClosedCircle, Point};
 
+	#[test]
+	fn new() {
+		assert_eq!(
+			ClosedCircle::new(Point{x: 1., y: 2.}, 3.),
+			ClosedCircle{center: Point{x: 1., y: 2.}, sq_radius: 9.},
+		);
+	}
+
 	#[test]
 	fn from_two_points() {
 		let p1 = Point{x: 4., y: -1.};
@@ -99,6 +483,126 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn try_from_three_points() {
+		let p1 = Point{x: 4., y: -1.};
+		let p2 = Point{x: 0., y: -1.};
+		let p3 = Point{x: 2., y: 1.};
+
+		assert_eq!(
+			ClosedCircle::try_from_three_points(p1, p2, p3),
+			Some(ClosedCircle::from_three_points(p1, p2, p3)),
+		);
+
+		// Collinear points
+		assert_eq!(
+			ClosedCircle::try_from_three_points(p1, p2, Point{x: 1., y: -1.}),
+			None,
+		);
+		// Exactly collinear, though rounding leaves the circumcenter finite
+		assert_eq!(
+			ClosedCircle::try_from_three_points(
+				Point{x: 1.6, y: 6.4}, Point{x: 2.6, y: 7.4}, Point{x: 3.6, y: 8.4},
+			),
+			None,
+		);
+		// Coincident points
+		assert_eq!(ClosedCircle::try_from_three_points(p1, p2, p2), None);
+		assert_eq!(ClosedCircle::try_from_three_points(p1, p1, p1), None);
+	}
+
+	#[test]
+	fn from_three_points_or_diameter() {
+		let p1 = Point{x: 4., y: -1.};
+		let p2 = Point{x: 0., y: -1.};
+		let p3 = Point{x: 2., y: 1.};
+
+		assert_eq!(
+			ClosedCircle::from_three_points_or_diameter(p1, p2, p3),
+			ClosedCircle::from_three_points(p1, p2, p3),
+		);
+
+		// Collinear points, in any order
+		let p3 = Point{x: 1., y: -1.};
+		let diameter_circle = ClosedCircle{
+			center: Point{x: 2., y: -1.},
+			sq_radius: 4.
+		};
+		assert_eq!(
+			ClosedCircle::from_three_points_or_diameter(p1, p2, p3),
+			diameter_circle,
+		);
+		assert_eq!(
+			ClosedCircle::from_three_points_or_diameter(p3, p1, p2),
+			diameter_circle,
+		);
+		assert_eq!(
+			ClosedCircle::from_three_points_or_diameter(p1, p3, p2),
+			diameter_circle,
+		);
+
+		// Coincident points
+		assert_eq!(
+			ClosedCircle::from_three_points_or_diameter(p1, p1, p1),
+			ClosedCircle::from_point(p1),
+		);
+	}
+
+	#[test]
+	fn covering() {
+		assert_eq!(ClosedCircle::<f64>::covering(&[]), None);
+
+		// Obtuse triangle is covered by the circle on its longest side
+		let p1 = Point{x: 4., y: -1.};
+		let p2 = Point{x: 0., y: -1.};
+		let p3 = Point{x: 2., y: 0.};
+		assert_eq!(
+			ClosedCircle::covering(&[p1, p2, p3]),
+			Some(ClosedCircle::from_two_points(p1, p2)),
+		);
+
+		// Acute triangle is covered by its circumcircle
+		let p3 = Point{x: 2., y: 2.};
+		assert_eq!(
+			ClosedCircle::covering(&[p3, p1, p2]),
+			Some(ClosedCircle::from_three_points(p1, p2, p3)),
+		);
+	}
+
+	#[test]
+	fn covering_depends_on_positions_only() {
+		// Nearly coincident points, the circle through which rounds
+		// differently from the one on either diameter
+		let points = [
+			Point{x: 2.617033646268938, y: 2.308516823134469},
+			Point{x: -5.000000000000005, y: -8.660254037844382},
+			Point{x: -5.000000000000007, y: -8.660254037844382},
+		];
+		let circle = ClosedCircle::covering(&points).unwrap();
+		for order in [[1, 0, 2], [2, 1, 0], [0, 2, 1]].iter() {
+			assert_eq!(ClosedCircle::covering(&order.iter().map(|i| points[*i]).collect::<Vec<_>>()), Some(circle));
+		}
+
+		// Four points on one circle, along with copies and points within
+		let square = [Point{x: 1., y: 0.}, Point{x: 0., y: 1.}, Point{x: -1., y: 0.}, Point{x: 0., y: -1.}];
+		let circle = ClosedCircle::covering(&square).unwrap();
+		let mut more = square.to_vec();
+		more.extend([Point{x: 0.5, y: 0.}, square[3], Point::default()].iter());
+		more.reverse();
+		assert_eq!(ClosedCircle::covering(&more), Some(circle));
+		assert_eq!(super::support(&more), Some(vec![square[2], square[3], square[1]]));
+	}
+
+	#[test]
+	fn covering_integers() {
+		// The circumcenter (2, -7/6) rounds to (2, -1), from which the corners
+		// below are farther than the apex
+		let points = [Point{x: 4, y: -2}, Point{x: 0, y: -2}, Point{x: 2, y: 1}];
+		let circle = ClosedCircle::covering(&points).unwrap();
+		assert_eq!(circle, ClosedCircle{center: Point{x: 2, y: -1}, sq_radius: 5});
+		assert!(points.iter().all(|p| circle.covers(*p)));
+	}
+
 	#[test]
 	fn covers() {
 		let circle = ClosedCircle{
@@ -121,4 +625,46 @@ mod tests {
 
 		assert_eq!(circle.radius(), 2.);
 	}
+
+	#[test]
+	fn covering_all_but() {
+		// Scattered over a small grid, so with duplicates and cocircular points
+		let points: Vec<Point> = (0..10_u64)
+			.map(|i| (i*37 + 11) % 23)
+			.map(|i| Point{x: (i % 5) as f64, y: ((i*3) % 7) as f64})
+			.collect();
+		for k in 0..=4 {
+			// Every way of leaving out at most k points
+			let smallest = (0..1_u32 << points.len())
+				.filter(|mask| mask.count_ones() as usize <= k)
+				.filter_map(|mask| {
+					let kept: Vec<Point> = (0..points.len())
+						.filter(|i| mask & (1 << i) == 0)
+						.map(|i| points[i])
+						.collect();
+					ClosedCircle::covering(&kept)
+				})
+				.map(|circle| circle.sq_radius)
+				.fold(f64::INFINITY, f64::min);
+
+			let (circle, outliers) = ClosedCircle::covering_all_but(&points, k).unwrap();
+			assert!((circle.sq_radius - smallest).abs() <= 1e-12 * smallest.max(1.), "{}", k);
+			let left_out = points.iter().filter(|p| outliers.contains(p)).count();
+			assert!(left_out <= k);
+			assert!(points.iter().all(|p| outliers.contains(p) || circle.covers(*p)));
+		}
+
+		// Greedily, past the exhaustive search
+		let mut points: Vec<Point> = (0..20).map(|i| Point{x: (i % 4) as f64, y: (i / 4) as f64}).collect();
+		let far: Vec<Point> = (1..=6).map(|i| Point{x: 100. * i as f64, y: -50.}).collect();
+		points.extend(far.iter().cloned());
+		let (circle, outliers) = ClosedCircle::covering_all_but(&points, 8).unwrap();
+		assert_eq!(outliers[..6], far[..]);
+		assert!(circle.radius() < 5.);
+
+		// Always keeps a point
+		let (circle, outliers) = ClosedCircle::covering_all_but(&far, 10).unwrap();
+		assert_eq!((circle.sq_radius, outliers.len()), (0., 5));
+		assert_eq!(ClosedCircle::<f64>::covering_all_but(&[], 2), None);
+	}
 }
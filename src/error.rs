@@ -0,0 +1,41 @@
+use crate::points::{Point, Point3};
+use crate::smallest_cover::PointHandle;
+
+use std::fmt;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Error {
+	// A point had a NaN or infinite coordinate
+	NonFinitePoint(Point),
+	NonFinitePoint3(Point3),
+	// A query or removal needed at least one point
+	EmptyCloud,
+	// A removal named a handle that refers to no logged point
+	UnknownHandle(PointHandle),
+	// The squared radius of a circle was too large for the scalar type
+	RadiusOverflow,
+	// A timestamp preceded that of the previous point
+	TimestampOutOfOrder,
+	// Internal structures disagreed with each other; indicates a bug
+	InconsistentState(&'static str),
+	// A snapshot broke the given invariant, and so could not be restored
+	InvalidSnapshot(&'static str),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::NonFinitePoint(p) => write!(formatter, "point {} has a non-finite coordinate", p),
+			Error::NonFinitePoint3(p) => write!(formatter, "point {} has a non-finite coordinate", p),
+			Error::EmptyCloud => write!(formatter, "point cloud is empty"),
+			Error::UnknownHandle(handle) => write!(formatter, "no logged point has handle {}", handle.0),
+			Error::RadiusOverflow => write!(formatter, "squared radius overflows the scalar type"),
+			Error::TimestampOutOfOrder => write!(formatter, "timestamps must be pushed in order"),
+			Error::InconsistentState(what) => write!(formatter, "inconsistent state: {}", what),
+			Error::InvalidSnapshot(what) => write!(formatter, "invalid snapshot: {}", what),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,236 @@
+use crate::points::Point;
+use crate::scalar::Scalar;
+use crate::circle::{self, ClosedCircle};
+use crate::smallest_cover::MovingPointCloud;
+use crate::error::Error;
+
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{ToPrimitive, Zero};
+
+
+// Exact arithmetic for integer points, to certify results of the
+// floating-point path. Hulls of integer points are already exact (see
+// `Scalar`); circles through them have rational centers, which are kept here
+// as exact fractions.
+
+// Point cloud whose hull is maintained in exact integer arithmetic, and whose
+// smallest covering circle is available exactly (see `exact_cover_circle`)
+pub type ExactPointCloud = MovingPointCloud<i32>;
+
+// Disc of all points within the radius of the center, boundary included
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExactCircle {
+	pub center: Point<BigRational>,
+	pub sq_radius: BigRational,
+}
+
+
+impl ExactCircle {
+	pub fn from_point<T: Scalar + Into<BigInt>>(p: Point<T>) -> Self {
+		Self{center: rational(p), sq_radius: BigRational::zero()}
+	}
+
+	pub fn from_two_points<T: Scalar + Into<BigInt>>(p1: Point<T>, p2: Point<T>) -> Self {
+		let (p1, p2) = (rational(p1), rational(p2));
+		let half = BigRational::new(1.into(), 2.into());
+		let center = Point{
+			x: (&p1.x + &p2.x) * &half,
+			y: (&p1.y + &p2.y) * &half,
+		};
+		let sq_radius = sq_dist(&center, &p1);
+		Self{center, sq_radius}
+	}
+
+	// Fails if the points are collinear (or coincide), in which case no
+	// circle passes through all three
+	pub fn try_from_three_points<T>(p1: Point<T>, p2: Point<T>, p3: Point<T>) -> Option<Self>
+	where
+		T: Scalar + Into<BigInt>,
+	{
+		// Relative to the first point
+		let (p1, p2, p3) = (integer(p1), integer(p2), integer(p3));
+		let (bx, by) = (&p2.x - &p1.x, &p2.y - &p1.y);
+		let (cx, cy) = (&p3.x - &p1.x, &p3.y - &p1.y);
+
+		let denominator: BigInt = 2 * (&bx*&cy - &by*&cx);
+		if denominator.is_zero() {
+			return None;
+		}
+		let (b_sq, c_sq) = (&bx*&bx + &by*&by, &cx*&cx + &cy*&cy);
+		let offset = Point{
+			x: BigRational::new(&cy*&b_sq - &by*&c_sq, denominator.clone()),
+			y: BigRational::new(&bx*&c_sq - &cx*&b_sq, denominator),
+		};
+
+		let sq_radius = &offset.x*&offset.x + &offset.y*&offset.y;
+		let center = Point{
+			x: offset.x + BigRational::from_integer(p1.x),
+			y: offset.y + BigRational::from_integer(p1.y),
+		};
+		Some(Self{center, sq_radius})
+	}
+
+	// Falls back to the circle whose diameter spans the two farthest-apart
+	// points, which covers the third when all three are collinear
+	pub fn from_three_points_or_diameter<T>(p1: Point<T>, p2: Point<T>, p3: Point<T>) -> Self
+	where
+		T: Scalar + Into<BigInt>,
+	{
+		Self::try_from_three_points(p1, p2, p3).unwrap_or_else(|| {
+			let (q1, q2) = circle::farthest_pair(p1, p2, p3);
+			Self::from_two_points(q1, q2)
+		})
+	}
+
+	pub fn covers<T: Scalar + Into<BigInt>>(&self, point: Point<T>) -> bool {
+		sq_dist(&self.center, &rational(point)) <= self.sq_radius
+	}
+
+	// Nearest in floating point
+	pub fn radius(&self) -> f64 {
+		self.sq_radius.to_f64().map_or(f64::NAN, f64::sqrt)
+	}
+
+	// Nearest in floating point; need not cover the points this circle does
+	pub fn to_f64(&self) -> ClosedCircle {
+		let to_f64 = |r: &BigRational| r.to_f64().unwrap_or(f64::NAN);
+		ClosedCircle{
+			center: Point{x: to_f64(&self.center.x), y: to_f64(&self.center.y)},
+			sq_radius: to_f64(&self.sq_radius),
+		}
+	}
+
+	// Smallest circle covering every point
+	pub fn covering<T: Scalar + Into<BigInt>>(points: &[Point<T>]) -> Option<Self> {
+		Some(match *circle::support(points)? {
+			[p1] => Self::from_point(p1),
+			[p1, p2] => Self::from_two_points(p1, p2),
+			[p1, p2, p3] => Self::from_three_points_or_diameter(p1, p2, p3),
+			_ => unreachable!(),
+		})
+	}
+}
+
+
+impl<T: Scalar + Into<BigInt>> MovingPointCloud<T> {
+	// Smallest covering circle, in exact arithmetic
+	pub fn exact_cover_circle(&self) -> Result<ExactCircle, Error> {
+		if self.is_empty() {
+			return Err(Error::EmptyCloud);
+		}
+		ExactCircle::covering(&self.hull_vertices())
+			.ok_or(Error::InconsistentState("hull of a non-empty cloud has no vertices"))
+	}
+}
+
+
+fn integer<T: Into<BigInt>>(p: Point<T>) -> Point<BigInt> {
+	Point{x: p.x.into(), y: p.y.into()}
+}
+
+fn rational<T: Into<BigInt>>(p: Point<T>) -> Point<BigRational> {
+	let p = integer(p);
+	Point{x: BigRational::from_integer(p.x), y: BigRational::from_integer(p.y)}
+}
+
+fn sq_dist(p1: &Point<BigRational>, p2: &Point<BigRational>) -> BigRational {
+	let (dx, dy) = (&p2.x - &p1.x, &p2.y - &p1.y);
+	&dx*&dx + &dy*&dy
+}
+
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+	use super::{ExactCircle, ExactPointCloud, BigRational, Point, Error};
+
+	fn ratio(numer: i64, denom: i64) -> BigRational {
+		BigRational::new(numer.into(), denom.into())
+	}
+
+	#[test]
+	fn from_two_points() {
+		let circle = ExactCircle::from_two_points(Point{x: 1, y: 0}, Point{x: 0, y: 2});
+		assert_eq!(circle.center, Point{x: ratio(1, 2), y: ratio(1, 1)});
+		assert_eq!(circle.sq_radius, ratio(5, 4));
+	}
+
+	#[test]
+	fn try_from_three_points() {
+		let (p1, p2, p3) = (Point{x: 4, y: -2}, Point{x: 0, y: -2}, Point{x: 2, y: 1});
+		let circle = ExactCircle::try_from_three_points(p1, p2, p3).unwrap();
+		assert_eq!(circle.center, Point{x: ratio(2, 1), y: ratio(-7, 6)});
+		assert_eq!(circle.sq_radius, ratio(169, 36));
+		assert!([p1, p2, p3].iter().all(|p| circle.covers(*p)));
+		assert!(!circle.covers(Point{x: 2, y: -4}));
+
+		assert_eq!(ExactCircle::try_from_three_points(p1, p2, Point{x: 9, y: -2}), None);
+		assert_eq!(
+			ExactCircle::from_three_points_or_diameter(p1, p2, Point{x: 9, y: -2}),
+			ExactCircle::from_two_points(p2, Point{x: 9, y: -2}),
+		);
+	}
+
+	#[test]
+	fn covering() {
+		// Beyond the precision of f64 at this offset, the exact circle
+		// still passes through its support points
+		let offset = 1_i64 << 56;
+		let points: Vec<Point<i64>> = [(4, -2), (0, -2), (2, 1), (2, 0)].iter()
+			.map(|(x, y)| Point{x: offset + x, y: offset + y})
+			.collect();
+		let circle = ExactCircle::covering(&points).unwrap();
+		assert_eq!(circle.sq_radius, ratio(169, 36));
+		assert!(points.iter().all(|p| circle.covers(*p)));
+		assert_eq!(ExactCircle::covering::<i64>(&[]), None);
+	}
+
+	#[test]
+	fn exact_cover_circle() {
+		let mut cloud = ExactPointCloud::default();
+		assert_eq!(cloud.exact_cover_circle(), Err(Error::EmptyCloud));
+
+		cloud.extend([(0, 0), (3, 0), (0, 4), (1, 1)].iter().map(|(x, y)| Point{x: *x, y: *y}));
+		let circle = cloud.exact_cover_circle().unwrap();
+		assert_eq!(circle.center, Point{x: ratio(3, 2), y: ratio(2, 1)});
+		assert_eq!(circle.radius(), 2.5);
+		assert_eq!(circle.to_f64().center, Point{x: 1.5, y: 2.});
+	}
+
+	#[test]
+	fn exact_cover_circle_overflow() {
+		// The squared radius fits no i32, though the exact circle is fine
+		let mut cloud = ExactPointCloud::default();
+		cloud.extend([(-60000, 0), (60000, 0), (0, 1)].iter().map(|(x, y)| Point{x: *x, y: *y}));
+		let circle = cloud.exact_cover_circle().unwrap();
+		assert_eq!(circle.center, Point{x: ratio(0, 1), y: ratio(0, 1)});
+		assert_eq!(circle.sq_radius, ratio(3_600_000_000, 1));
+		assert_eq!(cloud.try_cover_circle(), Err(Error::RadiusOverflow));
+		assert_eq!(cloud.checked_cover_circle(), None);
+
+		cloud.pop();
+		assert_eq!(cloud.try_cover_circle().map(|c| c.sq_radius), Ok(900_000_001));
+	}
+
+	#[test]
+	fn extreme_coordinates() {
+		let (min, max) = (i32::MIN, i32::MAX);
+		let corners = [Point{x: min, y: min}, Point{x: max, y: min}, Point{x: min, y: max}];
+		let mut cloud = ExactPointCloud::default();
+		cloud.extend(corners.iter().copied().chain(Some(Point{x: -1, y: -1})));
+		assert_eq!(cloud.validate(), Ok(()));
+		assert_eq!(cloud.hull_vertices(), corners.to_vec());
+
+		let circle = cloud.exact_cover_circle().unwrap();
+		assert_eq!(circle.center, Point{x: ratio(-1, 2), y: ratio(-1, 2)});
+		assert!(corners.iter().all(|p| circle.covers(*p)));
+		assert_eq!(cloud.try_cover_circle(), Err(Error::RadiusOverflow));
+
+		while cloud.len() > 1 {
+			cloud.pop();
+		}
+		assert_eq!(cloud.try_cover_circle().map(|c| c.sq_radius), Ok(0));
+	}
+}
@@ -0,0 +1,219 @@
+use crate::points::Point;
+use crate::polygon::ConvexPolygon;
+use crate::scalar::Scalar;
+use crate::smallest_cover::PointHandle;
+
+
+// Convex hull of a FIFO queue of points, kept as a two-stack queue: points are
+// pushed onto the back stack, and popped off the front stack, which is
+// refilled from the back stack whenever it runs out.
+//
+// Each stack keeps the hull of all its points. Insertions into either hull
+// are logged alongside their points, so that popping a point undoes its
+// insertion; the back hull is discarded wholesale on refilling the front.
+// Every point is thus inserted into a hull at most twice, and the hull of the
+// queue is the union of the two stacks' hulls. An insertion scans the hull,
+// so a push or pop takes O(h) amortized time for h hull vertices, not O(1).
+//
+// No per-stack summary brings that down to O(1): the smallest circle covering
+// two sets is not determined by the circles covering each, and merging two
+// hulls takes time in their size. What the two stacks save is the rescan of
+// the interior on every pop.
+#[derive(Debug)]
+pub struct FifoHull<T = f64> {
+	// Oldest point on top
+	front: Stack<T>,
+	front_hull: ConvexPolygon<T>,
+	// Newest point on top
+	back: Stack<T>,
+	back_hull: ConvexPolygon<T>,
+}
+
+// Points in the order they were inserted into the stack's hull, each with its
+// handle and the vertices its insertion removed
+type Stack<T> = Vec<(PointHandle, Point<T>, Vec<Point<T>>)>;
+
+impl<T: Scalar> FifoHull<T> {
+	pub fn new() -> Self {
+		Self{
+			front: Vec::new(),
+			front_hull: ConvexPolygon::default(),
+			back: Vec::new(),
+			back_hull: ConvexPolygon::default(),
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		self.front.len() + self.back.len()
+	}
+
+	// Handles must increase from one push to the next
+	pub fn push(&mut self, handle: PointHandle, point: Point<T>) {
+		let removed_vertices = self.back_hull.insert(point);
+		self.back.push((handle, point, removed_vertices));
+	}
+
+	// Removes the oldest point
+	pub fn pop(&mut self) -> Option<Point<T>> {
+		if self.front.is_empty() {
+			self.refill_front();
+		}
+		let (_handle, point, removed_vertices) = self.front.pop()?;
+		self.front_hull.undo_insert(point, removed_vertices);
+		Some(point)
+	}
+
+	// Removes the point with the given handle, by undoing the insertions into
+	// its stack's hull back to its own, and redoing the later ones; O(k h) for
+	// the k points inserted into that hull after it, so O(h) for the newest
+	// or oldest point
+	pub fn remove(&mut self, handle: PointHandle) -> Option<Point<T>> {
+		// Handles decrease up the front stack, and increase up the back stack
+		if let Ok(index) = self.front.binary_search_by(|(h, _p, _removed)| handle.cmp(h)) {
+			remove_from(&mut self.front, &mut self.front_hull, index)
+		} else if let Ok(index) = self.back.binary_search_by(|(h, _p, _removed)| h.cmp(&handle)) {
+			remove_from(&mut self.back, &mut self.back_hull, index)
+		} else {
+			None
+		}
+	}
+
+	fn refill_front(&mut self) {
+		for (handle, point, _removed_vertices) in self.back.drain(..).rev() {
+			let removed_vertices = self.front_hull.insert(point);
+			self.front.push((handle, point, removed_vertices));
+		}
+		self.back_hull = ConvexPolygon::default();
+	}
+
+	// Points in the queue, oldest first
+	pub(crate) fn points(&self) -> Vec<Point<T>> {
+		self.front.iter().rev()
+			.chain(self.back.iter())
+			.map(|(_handle, p, _removed)| *p)
+			.collect()
+	}
+
+	// Each stack's hull, with the points it holds
+	pub(crate) fn stacks(&self) -> [(&ConvexPolygon<T>, Vec<Point<T>>); 2] {
+		let points = |stack: &Stack<T>| stack.iter().map(|(_handle, p, _removed)| *p).collect();
+		[
+			(&self.front_hull, points(&self.front)),
+			(&self.back_hull, points(&self.back)),
+		]
+	}
+
+	// Hull vertices of both stacks; a superset of the queue's hull vertices
+	pub fn vertices(&self) -> Vec<Point<T>> {
+		let mut vertices = self.front_hull.positions().to_vec();
+		vertices.extend_from_slice(self.back_hull.positions());
+		vertices
+	}
+}
+
+// Removes the point at the given index of a stack, keeping its hull that of
+// the rest
+fn remove_from<T: Scalar>(stack: &mut Stack<T>, hull: &mut ConvexPolygon<T>, index: usize) -> Option<Point<T>> {
+	let mut undone = stack.split_off(index);
+	for (_handle, point, removed_vertices) in undone.iter_mut().rev() {
+		hull.undo_insert(*point, std::mem::take(removed_vertices));
+	}
+
+	let mut undone = undone.into_iter();
+	let (_handle, removed, _removed_vertices) = undone.next()?;
+	for (handle, point, _removed_vertices) in undone {
+		let removed_vertices = hull.insert(point);
+		stack.push((handle, point, removed_vertices));
+	}
+	Some(removed)
+}
+
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+	use super::{FifoHull, Point, PointHandle};
+	use crate::polygon::ConvexPolygon;
+
+	fn grid_points() -> Vec<Point> {
+		(0..60_u64)
+			.map(|i| (i*37 + 11) % 49)
+			.map(|i| Point{x: (i % 7) as f64, y: ((i*3) % 7) as f64 - (i / 7) as f64})
+			.collect()
+	}
+
+	fn same_hull(vertices: &[Point], points: &[Point]) -> bool {
+		let mut cp = ConvexPolygon::new();
+		for p in vertices.iter() {
+			cp.insert(*p);
+		}
+		let mut reference = ConvexPolygon::new();
+		for p in points.iter() {
+			reference.insert(*p);
+		}
+
+		points.iter().all(|p| cp.covers(*p))
+			&& vertices.iter().all(|p| reference.covers(*p))
+	}
+
+	#[test]
+	fn new() {
+		let hull: FifoHull = FifoHull::new();
+		assert_eq!(hull.len(), 0);
+		assert_eq!(hull.vertices(), vec!());
+	}
+
+	#[test]
+	fn push_pop() {
+		let points = grid_points();
+		let mut hull = FifoHull::new();
+
+		// Interleave pushes and pops, so the front is refilled several times
+		let mut oldest = 0;
+		for (i, p) in points.iter().enumerate() {
+			hull.push(PointHandle(i as u64), *p);
+			if i % 3 == 2 {
+				assert_eq!(hull.pop(), Some(points[oldest]));
+				oldest += 1;
+			}
+			assert_eq!(hull.len(), i+1 - oldest);
+			assert!(same_hull(&hull.vertices(), &points[oldest..=i]));
+		}
+
+		while oldest < points.len() {
+			assert_eq!(hull.pop(), Some(points[oldest]));
+			oldest += 1;
+			assert!(same_hull(&hull.vertices(), &points[oldest..]));
+		}
+		assert_eq!(hull.pop(), None);
+	}
+
+	#[test]
+	fn remove() {
+		let points = grid_points();
+		let mut hull = FifoHull::new();
+		for (i, p) in points[..10].iter().enumerate() {
+			hull.push(PointHandle(i as u64), *p);
+		}
+		hull.pop();
+		for (i, p) in points[10..20].iter().enumerate() {
+			hull.push(PointHandle(10 + i as u64), *p);
+		}
+
+		// From the front stack, and the back
+		let mut live: Vec<(u64, Point)> = (1..20).map(|i| (i, points[i as usize])).collect();
+		for i in [5, 12, 1, 19, 9] {
+			assert_eq!(hull.remove(PointHandle(i)), Some(points[i as usize]));
+			assert_eq!(hull.remove(PointHandle(i)), None);
+			live.retain(|(j, _p)| *j != i);
+			let live_points: Vec<Point> = live.iter().map(|(_i, p)| *p).collect();
+			assert_eq!(hull.points(), live_points);
+			assert!(same_hull(&hull.vertices(), &live_points));
+		}
+
+		for (_i, p) in live.iter() {
+			assert_eq!(hull.pop(), Some(*p));
+		}
+	}
+}
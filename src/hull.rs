@@ -0,0 +1,592 @@
+use crate::points::{Point, cmp_lexicographic};
+use crate::scalar::Scalar;
+
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+
+// Convex hull of a multiset of points, under arbitrary insertions and
+// removals (after Overmars & van Leeuwen).
+//
+// Points are kept in a treap ordered lexicographically by position. Each node
+// stores the upper hull of its subtree, and the upper hull of its subtree
+// rotated by half a turn (i.e. its lower hull), as persistent chains; a node's
+// chains are bridged together from those of its children, so that an update
+// only rebuilds the chains along one root path.
+#[derive(Debug, Clone, Default)]
+pub struct DynamicHull<T = f64> {
+	root: Link<T>,
+	len: usize,
+}
+
+type Link<T> = Option<Box<HullNode<T>>>;
+
+#[derive(Debug, Clone)]
+struct HullNode<T> {
+	point: Point<T>,
+	count: usize,
+	left: Link<T>,
+	right: Link<T>,
+	upper: Chain<T>,
+	lower: Chain<T>,
+}
+
+// Which of a subtree's chains were left unchanged by an update, and so are
+// also unchanged in every ancestor
+#[derive(Debug, Clone, Copy)]
+struct Unchanged {
+	upper: bool,
+	lower: bool,
+}
+
+const ALL_UNCHANGED: Unchanged = Unchanged{upper: true, lower: true};
+const ALL_CHANGED: Unchanged = Unchanged{upper: false, lower: false};
+
+// Persistent treap over the vertices of an upper hull; shared atomically, so
+// that clouds can be shared across threads
+type Chain<T> = Option<Arc<ChainNode<T>>>;
+
+#[derive(Debug)]
+struct ChainNode<T> {
+	point: Point<T>,
+	first: Point<T>,
+	last: Point<T>,
+	left: Chain<T>,
+	right: Chain<T>,
+}
+
+
+// Which hull a chain holds. The lower hull is kept as the upper hull of the
+// points turned by half a turn, but without negating them (which overflows
+// for integers): turning reverses their order and preserves orientation.
+#[derive(Debug, Clone, Copy)]
+enum Side {
+	Upper,
+	Lower,
+}
+
+impl Side {
+	fn cmp<T: Scalar>(self, p1: &Point<T>, p2: &Point<T>) -> Ordering {
+		match self {
+			Side::Upper => cmp_lexicographic(p1, p2),
+			Side::Lower => cmp_lexicographic(p2, p1),
+		}
+	}
+}
+
+// Treap priority, derived from the point itself (splitmix64)
+fn priority<T: Scalar>(p: Point<T>) -> u64 {
+	let (x, y) = p.to_f64().to_bits();
+	let mut z = x ^ y.rotate_left(32) ^ 0x9e37_79b9_7f4a_7c15;
+	z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+	z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+	z ^ (z >> 31)
+}
+
+
+//-----------------------------------------------------------------------------
+// Hull Chains
+//-----------------------------------------------------------------------------
+
+fn chain_node<T: Scalar>(point: Point<T>, left: Chain<T>, right: Chain<T>) -> Chain<T> {
+	Some(Arc::new(ChainNode{
+		point,
+		first: left.as_ref().map_or(point, |n| n.first),
+		last: right.as_ref().map_or(point, |n| n.last),
+		left,
+		right,
+	}))
+}
+
+// Concatenates two chains, where every point of `c1` precedes those of `c2`
+fn chain_join<T: Scalar>(c1: &Chain<T>, c2: &Chain<T>) -> Chain<T> {
+	match (c1, c2) {
+		(None, _) => c2.clone(),
+		(_, None) => c1.clone(),
+		(Some(n1), Some(n2)) => {
+			if priority(n1.point) >= priority(n2.point) {
+				chain_node(n1.point, n1.left.clone(), chain_join(&n1.right, c2))
+			} else {
+				chain_node(n2.point, chain_join(c1, &n2.left), n2.right.clone())
+			}
+		}
+	}
+}
+
+// Splits a chain into the points before and after `key`, with `key` itself
+// going to the former if `key_goes_left`
+fn chain_split<T: Scalar>(side: Side, chain: &Chain<T>, key: Point<T>, key_goes_left: bool) -> (Chain<T>, Chain<T>) {
+	match chain {
+		None => (None, None),
+		Some(n) => {
+			let goes_left = match side.cmp(&n.point, &key) {
+				Ordering::Less => true,
+				Ordering::Equal => key_goes_left,
+				Ordering::Greater => false,
+			};
+			if goes_left {
+				let (c1, c2) = chain_split(side, &n.right, key, key_goes_left);
+				(chain_node(n.point, n.left.clone(), c1), c2)
+			} else {
+				let (c1, c2) = chain_split(side, &n.left, key, key_goes_left);
+				(c1, chain_node(n.point, c2, n.right.clone()))
+			}
+		}
+	}
+}
+
+// Finds the first vertex of a chain at which to stop, where `go_past` takes
+// a vertex and its successor (if any), and holds for some prefix of the chain
+fn chain_search<T: Scalar, F>(chain: &Chain<T>, go_past: F) -> Option<Point<T>>
+where
+	F: Fn(Point<T>, Option<Point<T>>) -> bool,
+{
+	let mut node = chain.as_ref();
+	let mut successor = None;
+	let mut found = None;
+
+	while let Some(n) = node {
+		let next = n.right.as_ref().map(|r| r.first).or(successor);
+		if go_past(n.point, next) {
+			node = n.right.as_ref();
+		} else {
+			found = Some(n.point);
+			successor = Some(n.point);
+			node = n.left.as_ref();
+		}
+	}
+
+	found
+}
+
+// Merges the upper hulls of two point sets, where every point of the first
+// precedes those of the second, by finding the bridge between them; a search
+// of one chain that finds a tangent to the other at each step, so O(log² n)
+fn chain_bridge<T: Scalar>(side: Side, c1: &Chain<T>, c2: &Chain<T>) -> Chain<T> {
+	if c1.is_none() || c2.is_none() {
+		return chain_join(c1, c2);
+	}
+
+	// Farthest vertex of `c2` on the tangent from `p`
+	let tangent = |p: Point<T>| chain_search(c2, |q, next| {
+		next.is_some_and(|s| T::orient2d(p, q, s) >= Some(Ordering::Equal))
+	}).unwrap();
+	// Nearest vertex of `c1` on the bridge
+	let p = chain_search(c1, |p, next| {
+		next.is_some_and(|s| T::orient2d(p, tangent(p), s) == Some(Ordering::Greater))
+	}).unwrap();
+	let q = tangent(p);
+
+	chain_join(&chain_split(side, c1, p, true).0, &chain_split(side, c2, q, false).1)
+}
+
+fn chain_contains<T: Scalar>(side: Side, chain: &Chain<T>, point: Point<T>) -> bool {
+	let mut node = chain.as_ref();
+	while let Some(n) = node {
+		node = match side.cmp(&point, &n.point) {
+			Ordering::Less => n.left.as_ref(),
+			Ordering::Greater => n.right.as_ref(),
+			Ordering::Equal => return true,
+		};
+	}
+	false
+}
+
+// Whether `point` lies on or below the upper hull (on or above the lower
+// hull), within its span
+fn chain_covers<T: Scalar>(side: Side, chain: &Chain<T>, point: Point<T>) -> bool {
+	let within_span = chain.as_ref().is_some_and(|n| {
+		side.cmp(&n.first, &point) != Ordering::Greater
+		&& side.cmp(&point, &n.last) != Ordering::Greater
+	});
+	if !within_span {
+		return false;
+	}
+
+	// Find the chain edge spanning the point
+	let mut node = chain.as_ref();
+	let mut prev = None;
+	let mut next = None;
+	while let Some(n) = node {
+		if side.cmp(&n.point, &point) == Ordering::Greater {
+			next = Some(n.point);
+			node = n.left.as_ref();
+		} else {
+			prev = Some(n.point);
+			node = n.right.as_ref();
+		}
+	}
+
+	match (prev, next) {
+		(Some(p1), Some(p2)) => matches!(
+			T::orient2d(p1, p2, point),
+			Some(Ordering::Less) | Some(Ordering::Equal)
+		),
+		_ => true,
+	}
+}
+
+fn chain_points<T: Scalar>(chain: &Chain<T>, points: &mut Vec<Point<T>>) {
+	if let Some(n) = chain {
+		chain_points(&n.left, points);
+		points.push(n.point);
+		chain_points(&n.right, points);
+	}
+}
+
+
+//-----------------------------------------------------------------------------
+// Point Treap
+//-----------------------------------------------------------------------------
+
+impl<T: Scalar> HullNode<T> {
+	fn new(point: Point<T>) -> Box<Self> {
+		let mut node = Box::new(Self{
+			point,
+			count: 1,
+			left: None,
+			right: None,
+			upper: None,
+			lower: None,
+		});
+		node.update();
+		node
+	}
+
+	fn update(&mut self) {
+		self.update_upper();
+		self.update_lower();
+	}
+
+	fn update_upper(&mut self) {
+		let upper = |link: &Link<T>| link.as_ref().and_then(|n| n.upper.clone());
+		self.upper = chain_bridge(
+			Side::Upper,
+			&chain_bridge(Side::Upper, &upper(&self.left), &chain_node(self.point, None, None)),
+			&upper(&self.right),
+		);
+	}
+
+	fn update_lower(&mut self) {
+		let lower = |link: &Link<T>| link.as_ref().and_then(|n| n.lower.clone());
+		self.lower = chain_bridge(
+			Side::Lower,
+			&chain_bridge(Side::Lower, &lower(&self.right), &chain_node(self.point, None, None)),
+			&lower(&self.left),
+		);
+	}
+
+	// Updates the chains after `point` joined the subtree; a chain only
+	// changes if the point is outside it
+	fn update_inserted(&mut self, point: Point<T>, unchanged: Unchanged) -> Unchanged {
+		let unchanged = Unchanged{
+			upper: unchanged.upper || chain_covers(Side::Upper, &self.upper, point),
+			lower: unchanged.lower || chain_covers(Side::Lower, &self.lower, point),
+		};
+		if !unchanged.upper {
+			self.update_upper();
+		}
+		if !unchanged.lower {
+			self.update_lower();
+		}
+		unchanged
+	}
+
+	// Updates the chains after `point` left the subtree; a chain only
+	// changes if the point was one of its vertices
+	fn update_removed(&mut self, point: Point<T>, unchanged: Unchanged) -> Unchanged {
+		let unchanged = Unchanged{
+			upper: unchanged.upper || !chain_contains(Side::Upper, &self.upper, point),
+			lower: unchanged.lower || !chain_contains(Side::Lower, &self.lower, point),
+		};
+		if !unchanged.upper {
+			self.update_upper();
+		}
+		if !unchanged.lower {
+			self.update_lower();
+		}
+		unchanged
+	}
+}
+
+fn rotate_left<T: Scalar>(mut node: Box<HullNode<T>>) -> Box<HullNode<T>> {
+	let mut right = node.right.take().unwrap();
+	node.right = right.left.take();
+	node.update();
+	right.left = Some(node);
+	right.update();
+	right
+}
+
+fn rotate_right<T: Scalar>(mut node: Box<HullNode<T>>) -> Box<HullNode<T>> {
+	let mut left = node.left.take().unwrap();
+	node.left = left.right.take();
+	node.update();
+	left.right = Some(node);
+	left.update();
+	left
+}
+
+fn insert<T: Scalar>(link: Link<T>, point: Point<T>) -> (Box<HullNode<T>>, Unchanged) {
+	let mut node = match link {
+		None => return (HullNode::new(point), ALL_CHANGED),
+		Some(node) => node,
+	};
+
+	let unchanged = match cmp_lexicographic(&point, &node.point) {
+		Ordering::Equal => {
+			node.count += 1;
+			return (node, ALL_UNCHANGED);
+		},
+		Ordering::Less => {
+			let (child, unchanged) = insert(node.left.take(), point);
+			let rotate = priority(child.point) > priority(node.point);
+			node.left = Some(child);
+			if rotate {
+				return (rotate_right(node), ALL_CHANGED);
+			}
+			unchanged
+		},
+		Ordering::Greater => {
+			let (child, unchanged) = insert(node.right.take(), point);
+			let rotate = priority(child.point) > priority(node.point);
+			node.right = Some(child);
+			if rotate {
+				return (rotate_left(node), ALL_CHANGED);
+			}
+			unchanged
+		},
+	};
+
+	let unchanged = node.update_inserted(point, unchanged);
+	(node, unchanged)
+}
+
+// Returns `None` if the point is not in the treap
+fn remove<T: Scalar>(link: &mut Link<T>, point: Point<T>) -> Option<Unchanged> {
+	let node = link.as_deref_mut()?;
+
+	let unchanged = match cmp_lexicographic(&point, &node.point) {
+		Ordering::Less => remove(&mut node.left, point)?,
+		Ordering::Greater => remove(&mut node.right, point)?,
+		Ordering::Equal if node.count > 1 => {
+			node.count -= 1;
+			return Some(ALL_UNCHANGED);
+		},
+		Ordering::Equal => {
+			let node = link.take().unwrap();
+			*link = merge(node.left, node.right);
+			return Some(ALL_CHANGED);
+		},
+	};
+
+	Some(node.update_removed(point, unchanged))
+}
+
+// Merges two treaps, where every point of `link1` precedes those of `link2`
+fn merge<T: Scalar>(link1: Link<T>, link2: Link<T>) -> Link<T> {
+	match (link1, link2) {
+		(None, link) | (link, None) => link,
+		(Some(mut node1), Some(mut node2)) => {
+			if priority(node1.point) > priority(node2.point) {
+				node1.right = merge(node1.right.take(), Some(node2));
+				node1.update();
+				Some(node1)
+			} else {
+				node2.left = merge(Some(node1), node2.left.take());
+				node2.update();
+				Some(node2)
+			}
+		}
+	}
+}
+
+
+//-----------------------------------------------------------------------------
+// Dynamic Hull
+//-----------------------------------------------------------------------------
+
+impl<T: Scalar> DynamicHull<T> {
+	pub fn new() -> Self {
+		Self{root: None, len: 0}
+	}
+
+	// Number of points, counting duplicates
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn insert(&mut self, point: Point<T>) {
+		let (root, _unchanged) = insert(self.root.take(), point);
+		self.root = Some(root);
+		self.len += 1;
+	}
+
+	// Removes one copy of the point; returns false if there is none
+	pub fn remove(&mut self, point: Point<T>) -> bool {
+		if remove(&mut self.root, point).is_none() {
+			return false;
+		}
+		self.len -= 1;
+		true
+	}
+
+	// Hull vertices in counter-clockwise order, starting from the
+	// lexicographically smallest point
+	pub fn vertices(&self) -> Vec<Point<T>> {
+		let root = match &self.root {
+			None => return Vec::new(),
+			Some(root) => root,
+		};
+
+		let mut vertices = Vec::new();
+		chain_points(&root.lower, &mut vertices);
+		vertices.reverse();
+
+		let mut upper = Vec::new();
+		chain_points(&root.upper, &mut upper);
+		if upper.len() > 2 {
+			vertices.extend(upper[1..upper.len()-1].iter().rev());
+		}
+
+		vertices
+	}
+
+	// Every point, duplicates included, in lexicographic order
+	pub(crate) fn points(&self) -> Vec<Point<T>> {
+		fn collect<T: Scalar>(link: &Link<T>, points: &mut Vec<Point<T>>) {
+			if let Some(node) = link {
+				collect(&node.left, points);
+				points.extend(std::iter::repeat_n(node.point, node.count));
+				collect(&node.right, points);
+			}
+		}
+
+		let mut points = Vec::with_capacity(self.len);
+		collect(&self.root, &mut points);
+		points
+	}
+}
+
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+	use super::{DynamicHull, Point, cmp_lexicographic};
+	use crate::polygon::ConvexPolygon;
+
+	fn grid_points() -> Vec<Point> {
+		// Scattered over a small grid, to produce duplicates and collinearities
+		(0..60_u64)
+			.map(|i| (i*37 + 11) % 49)
+			.map(|i| Point{x: (i % 7) as f64, y: ((i*3) % 7) as f64 - (i / 7) as f64})
+			.collect()
+	}
+
+	fn reference_vertices(points: &[Point]) -> Vec<Point> {
+		let mut cp = ConvexPolygon::new();
+		for p in points.iter() {
+			cp.insert(*p);
+		}
+		let mut vertices = cp.positions().to_vec();
+
+		// Rotate to start from the lexicographically smallest vertex
+		let start = (0..vertices.len())
+			.min_by(|i, j| cmp_lexicographic(&vertices[*i], &vertices[*j]))
+			.unwrap_or(0);
+		vertices.rotate_left(start);
+		vertices
+	}
+
+	#[test]
+	fn new() {
+		let hull: DynamicHull = DynamicHull::new();
+		assert_eq!(hull.len(), 0);
+		assert_eq!(hull.vertices(), vec!());
+	}
+
+	#[test]
+	fn vertices() {
+		let mut hull = DynamicHull::new();
+		hull.insert(Point{x: 0., y: 1.});
+		assert_eq!(hull.vertices(), vec!(Point{x: 0., y: 1.}));
+
+		hull.insert(Point{x: 2., y: 0.});
+		hull.insert(Point{x: 1., y: 0.5});
+		assert_eq!(hull.vertices(), vec!(Point{x: 0., y: 1.}, Point{x: 2., y: 0.}));
+
+		hull.insert(Point{x: -1., y: -1.});
+		hull.insert(Point{x: 1., y: -1.});
+		hull.insert(Point{x: 0., y: 0.});
+		assert_eq!(hull.vertices(), vec!(
+			Point{x: -1., y: -1.},
+			Point{x: 1., y: -1.},
+			Point{x: 2., y: 0.},
+			Point{x: 0., y: 1.},
+		));
+	}
+
+	#[test]
+	fn vertical_edges() {
+		let mut hull = DynamicHull::new();
+		for p in [(0., 0.), (0., 2.), (0., 1.), (1., 1.), (1., 3.)].iter() {
+			hull.insert(Point{x: p.0, y: p.1});
+		}
+		assert_eq!(hull.vertices(), vec!(
+			Point{x: 0., y: 0.},
+			Point{x: 1., y: 1.},
+			Point{x: 1., y: 3.},
+			Point{x: 0., y: 2.},
+		));
+	}
+
+	#[test]
+	fn insert() {
+		let points = grid_points();
+		let mut hull = DynamicHull::new();
+
+		for (i, p) in points.iter().enumerate() {
+			hull.insert(*p);
+			assert_eq!(hull.len(), i+1);
+			assert_eq!(hull.vertices(), reference_vertices(&points[..=i]));
+		}
+	}
+
+	#[test]
+	fn remove() {
+		let points = grid_points();
+		let mut hull = DynamicHull::new();
+		for p in points.iter() {
+			hull.insert(*p);
+		}
+
+		// Remove from alternating ends, to exercise both old and new points
+		let mut live = points.clone();
+		while !live.is_empty() {
+			let p = if live.len().is_multiple_of(2) {live.remove(0)} else {live.pop().unwrap()};
+			assert!(hull.remove(p));
+			assert_eq!(hull.len(), live.len());
+			assert_eq!(hull.vertices(), reference_vertices(&live));
+		}
+		assert!(!hull.remove(Point{x: 0., y: 0.}));
+	}
+
+	#[test]
+	fn duplicates() {
+		let mut hull = DynamicHull::new();
+		let p = Point{x: 1., y: 2.};
+		hull.insert(p);
+		hull.insert(p);
+		hull.insert(Point{x: 0., y: 0.});
+		assert_eq!(hull.len(), 3);
+
+		assert!(hull.remove(p));
+		assert_eq!(hull.len(), 2);
+		assert_eq!(hull.vertices(), vec!(Point{x: 0., y: 0.}, p));
+		assert!(hull.remove(p));
+		assert_eq!(hull.len(), 1);
+		assert_eq!(hull.vertices(), vec!(Point{x: 0., y: 0.}));
+		assert!(!hull.remove(p));
+	}
+}
@@ -1,4 +1,14 @@
 pub mod smallest_cover;
 pub mod points;
-mod polygon;
-mod circle;
+pub mod scalar;
+pub mod window;
+pub mod error;
+mod hull;
+mod fifo;
+mod predicates;
+pub mod polygon;
+pub mod circle;
+pub mod sphere;
+pub mod ball;
+#[cfg(feature = "exact")]
+pub mod exact;
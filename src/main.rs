@@ -1,3 +1,539 @@
+use smallest_circle::circle::ClosedCircle;
+use smallest_circle::points::Point;
+use smallest_circle::smallest_cover::{Backend, MovingPointCloud};
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::collections::VecDeque;
+use std::process;
+
+
+const USAGE: &str = "\
+Usage: smallest-circle [OPTIONS] [FILE]
+
+Computes the smallest circle enclosing the points read from FILE, or from
+standard input if FILE is absent or '-'.
+
+Options:
+  -f, --format <FORMAT>   Input format: text, csv or json (default: from the
+                          file extension, else text)
+  -o, --output <FORMAT>   Output format: text, csv or json (default: text)
+  -w, --window <N>        Emit the circle covering the last N points after
+                          every point read
+  -h, --help              Print this message
+
+Input formats:
+  text   whitespace-separated coordinates, taken in (x, y) pairs
+  csv    one 'x,y' point per line, after an optional header line
+  json   an array of [x, y] arrays or {\"x\": .., \"y\": ..} objects
+";
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+	Text,
+	Csv,
+	Json,
+}
+
+#[derive(Debug, PartialEq)]
+struct Options {
+	input_format: Option<Format>,
+	output_format: Format,
+	window: Option<usize>,
+	path: Option<String>,
+}
+
+type PointResult = Result<Point, String>;
+
+
 fn main() {
-    println!("Hello, world!");
+	let options = match parse_args(std::env::args().skip(1)) {
+		Ok(Some(options)) => options,
+		Ok(None) => {
+			print!("{}", USAGE);
+			return;
+		},
+		Err(message) => {
+			eprintln!("error: {}\n\n{}", message, USAGE);
+			process::exit(2);
+		},
+	};
+
+	if let Err(message) = run(options) {
+		eprintln!("error: {}", message);
+		process::exit(1);
+	}
+}
+
+fn run(options: Options) -> Result<(), String> {
+	let path = options.path.as_deref().filter(|path| *path != "-");
+	let input: Box<dyn BufRead> = match path {
+		Some(path) => Box::new(BufReader::new(
+			File::open(path).map_err(|e| format!("cannot open {}: {}", path, e))?
+		)),
+		None => Box::new(BufReader::new(io::stdin())),
+	};
+	let input_format = options.input_format
+		.or_else(|| path.and_then(format_from_extension))
+		.unwrap_or(Format::Text);
+	let points = read_points(input, input_format)?;
+
+	let stdout = io::stdout();
+	let mut output = stdout.lock();
+	let write_error = |e: io::Error| format!("cannot write output: {}", e);
+
+	if options.output_format == Format::Csv {
+		writeln!(output, "x,y,radius").map_err(write_error)?;
+	}
+
+	// Points leave the window oldest first
+	let mut point_cloud = MovingPointCloud::with_backend(Backend::Fifo);
+	for p in points {
+		point_cloud.try_push(p?).map_err(|e| e.to_string())?;
+		match options.window {
+			None => continue,
+			Some(window) if point_cloud.len() > window => {
+				point_cloud.pop();
+			},
+			Some(_) => {},
+		}
+		write_circle(&mut output, &point_cloud.cover_circle(), options.output_format)
+			.map_err(write_error)?;
+	}
+
+	if options.window.is_none() {
+		if point_cloud.is_empty() {
+			return Err("no points in input".to_string());
+		}
+		write_circle(&mut output, &point_cloud.cover_circle(), options.output_format)
+			.map_err(write_error)?;
+	}
+
+	output.flush().map_err(write_error)
+}
+
+
+//-----------------------------------------------------------------------------
+// Arguments
+//-----------------------------------------------------------------------------
+
+// Returns `None` if help was requested
+fn parse_args<I: Iterator<Item=String>>(mut args: I) -> Result<Option<Options>, String> {
+	let mut options = Options{
+		input_format: None,
+		output_format: Format::Text,
+		window: None,
+		path: None,
+	};
+
+	while let Some(arg) = args.next() {
+		let mut value = |name: &str| args.next()
+			.ok_or_else(|| format!("missing value for {}", name));
+
+		match arg.as_str() {
+			"-h" | "--help" => return Ok(None),
+			"-f" | "--format" => {
+				options.input_format = Some(parse_format(&value(&arg)?)?);
+			},
+			"-o" | "--output" => {
+				options.output_format = parse_format(&value(&arg)?)?;
+			},
+			"-w" | "--window" => {
+				let text = value(&arg)?;
+				options.window = match text.parse::<usize>() {
+					Ok(window) if window > 0 => Some(window),
+					_ => return Err(format!("invalid window size '{}'", text)),
+				};
+			},
+			_ if arg.starts_with('-') && arg != "-" => {
+				return Err(format!("unknown option '{}'", arg));
+			},
+			_ if options.path.is_some() => {
+				return Err(format!("unexpected argument '{}'", arg));
+			},
+			_ => options.path = Some(arg),
+		}
+	}
+
+	Ok(Some(options))
+}
+
+fn parse_format(text: &str) -> Result<Format, String> {
+	match text {
+		"text" | "txt" => Ok(Format::Text),
+		"csv" => Ok(Format::Csv),
+		"json" => Ok(Format::Json),
+		_ => Err(format!("unknown format '{}'", text)),
+	}
+}
+
+fn format_from_extension(path: &str) -> Option<Format> {
+	let extension = std::path::Path::new(path).extension()?.to_str()?;
+	parse_format(&extension.to_ascii_lowercase()).ok()
+}
+
+
+//-----------------------------------------------------------------------------
+// Input
+//-----------------------------------------------------------------------------
+
+// Text and CSV input are read lazily, so that a window can follow a stream;
+// JSON input is read in full before parsing
+fn read_points<'a, R: BufRead + 'a>(mut input: R, format: Format)
+	-> Result<Box<dyn Iterator<Item=PointResult> + 'a>, String>
+{
+	Ok(match format {
+		Format::Text => Box::new(TextPoints{lines: input.lines().enumerate(), pending: VecDeque::new()}),
+		Format::Csv => Box::new(CsvPoints{lines: input.lines().enumerate(), header_allowed: true}),
+		Format::Json => {
+			let mut text = String::new();
+			input.read_to_string(&mut text)
+				.map_err(|e| format!("cannot read input: {}", e))?;
+			Box::new(parse_json_points(&text)?.into_iter().map(Ok))
+		},
+	})
+}
+
+fn parse_number(text: &str, line: usize) -> Result<f64, String> {
+	text.trim().parse::<f64>()
+		.map_err(|_| format!("line {}: invalid number '{}'", line+1, text.trim()))
+}
+
+struct TextPoints<L> {
+	lines: std::iter::Enumerate<L>,
+	// Coordinates read but not yet paired, with their lines
+	pending: VecDeque<(usize, f64)>,
+}
+
+impl<L: Iterator<Item=io::Result<String>>> Iterator for TextPoints<L> {
+	type Item = PointResult;
+
+	fn next(&mut self) -> Option<PointResult> {
+		while self.pending.len() < 2 {
+			let (i, line) = match self.lines.next() {
+				None => {
+					let (i, _) = self.pending.pop_front()?;
+					return Some(Err(format!("line {}: unpaired coordinate", i+1)));
+				},
+				Some((_, Err(e))) => return Some(Err(format!("cannot read input: {}", e))),
+				Some((i, Ok(line))) => (i, line),
+			};
+			for word in line.split_whitespace() {
+				match parse_number(word, i) {
+					Ok(coord) => self.pending.push_back((i, coord)),
+					Err(message) => return Some(Err(message)),
+				}
+			}
+		}
+
+		let (_, x) = self.pending.pop_front().unwrap();
+		let (_, y) = self.pending.pop_front().unwrap();
+		Some(Ok(Point{x, y}))
+	}
+}
+
+struct CsvPoints<L> {
+	lines: std::iter::Enumerate<L>,
+	header_allowed: bool,
+}
+
+impl<L: Iterator<Item=io::Result<String>>> Iterator for CsvPoints<L> {
+	type Item = PointResult;
+
+	fn next(&mut self) -> Option<PointResult> {
+		for (i, line) in &mut self.lines {
+			let line = match line {
+				Ok(line) => line,
+				Err(e) => return Some(Err(format!("cannot read input: {}", e))),
+			};
+			if line.trim().is_empty() {
+				continue;
+			}
+
+			let fields: Vec<&str> = line.split(',').collect();
+			if fields.len() < 2 {
+				return Some(Err(format!("line {}: expected 'x,y'", i+1)));
+			}
+			let header_allowed = std::mem::replace(&mut self.header_allowed, false);
+			match (parse_number(fields[0], i), parse_number(fields[1], i)) {
+				(Ok(x), Ok(y)) => return Some(Ok(Point{x, y})),
+				(Err(_), Err(_)) if header_allowed => continue,
+				(Err(message), _) | (_, Err(message)) => return Some(Err(message)),
+			}
+		}
+		None
+	}
+}
+
+// Parses the subset of JSON needed for point lists: an array of `[x, y]`
+// arrays or of objects with numeric "x" and "y" members (other members, of
+// any type, are ignored)
+fn parse_json_points(text: &str) -> Result<Vec<Point>, String> {
+	let mut parser = JsonParser{text: text.as_bytes(), pos: 0};
+	let points = parser.point_array()?;
+	parser.skip_whitespace();
+	if parser.pos != text.len() {
+		return Err(parser.error("trailing characters"));
+	}
+	Ok(points)
+}
+
+struct JsonParser<'a> {
+	text: &'a [u8],
+	pos: usize,
+}
+
+impl JsonParser<'_> {
+	fn error(&self, message: &str) -> String {
+		let line = self.text[..self.pos].iter().filter(|c| **c == b'\n').count();
+		format!("line {}: {}", line+1, message)
+	}
+
+	fn skip_whitespace(&mut self) {
+		while self.text.get(self.pos).is_some_and(|c| c.is_ascii_whitespace()) {
+			self.pos += 1;
+		}
+	}
+
+	fn peek(&mut self) -> Option<u8> {
+		self.skip_whitespace();
+		self.text.get(self.pos).cloned()
+	}
+
+	fn expect(&mut self, c: u8) -> Result<(), String> {
+		if self.peek() != Some(c) {
+			return Err(self.error(&format!("expected '{}'", c as char)));
+		}
+		self.pos += 1;
+		Ok(())
+	}
+
+	// Parses the items of a delimited, comma-separated list
+	fn list<F>(&mut self, open: u8, close: u8, mut item: F) -> Result<(), String>
+	where
+		F: FnMut(&mut Self) -> Result<(), String>,
+	{
+		self.expect(open)?;
+		if self.peek() == Some(close) {
+			self.pos += 1;
+			return Ok(());
+		}
+		loop {
+			item(self)?;
+			match self.peek() {
+				Some(b',') => self.pos += 1,
+				Some(c) if c == close => {
+					self.pos += 1;
+					return Ok(());
+				},
+				_ => return Err(self.error(&format!("expected ',' or '{}'", close as char))),
+			}
+		}
+	}
+
+	fn number(&mut self) -> Result<f64, String> {
+		self.skip_whitespace();
+		let start = self.pos;
+		while self.text.get(self.pos)
+			.is_some_and(|c| c.is_ascii_digit() || b"+-.eE".contains(c))
+		{
+			self.pos += 1;
+		}
+		std::str::from_utf8(&self.text[start..self.pos]).ok()
+			.and_then(|s| s.parse::<f64>().ok())
+			.ok_or_else(|| self.error("expected a number"))
+	}
+
+	fn string(&mut self) -> Result<String, String> {
+		self.expect(b'"')?;
+		let start = self.pos;
+		while let Some(c) = self.text.get(self.pos) {
+			match c {
+				b'"' => {
+					let s = String::from_utf8_lossy(&self.text[start..self.pos]).into_owned();
+					self.pos += 1;
+					return Ok(s);
+				},
+				b'\\' => self.pos += 2,
+				_ => self.pos += 1,
+			}
+		}
+		Err(self.error("unterminated string"))
+	}
+
+	// Consumes any value: a string, number, object, array or literal
+	fn skip_value(&mut self) -> Result<(), String> {
+		match self.peek() {
+			Some(b'"') => self.string().map(|_s| ()),
+			Some(b'{') => self.list(b'{', b'}', |parser| {
+				parser.string()?;
+				parser.expect(b':')?;
+				parser.skip_value()
+			}),
+			Some(b'[') => self.list(b'[', b']', |parser| parser.skip_value()),
+			Some(c) if c.is_ascii_alphabetic() => {
+				let start = self.pos;
+				while self.text.get(self.pos).is_some_and(|c| c.is_ascii_alphabetic()) {
+					self.pos += 1;
+				}
+				match &self.text[start..self.pos] {
+					b"true" | b"false" | b"null" => Ok(()),
+					_ => Err(self.error("expected a value")),
+				}
+			},
+			_ => self.number().map(|_n| ()),
+		}
+	}
+
+	fn point_array(&mut self) -> Result<Vec<Point>, String> {
+		let mut points = Vec::new();
+		self.list(b'[', b']', |parser| {
+			points.push(parser.point()?);
+			Ok(())
+		})?;
+		Ok(points)
+	}
+
+	fn point(&mut self) -> Result<Point, String> {
+		let mut x = None;
+		let mut y = None;
+
+		match self.peek() {
+			Some(b'[') => {
+				let mut coords = Vec::new();
+				self.list(b'[', b']', |parser| {
+					coords.push(parser.number()?);
+					Ok(())
+				})?;
+				if let [cx, cy] = coords[..] {
+					x = Some(cx);
+					y = Some(cy);
+				}
+			},
+			Some(b'{') => {
+				self.list(b'{', b'}', |parser| {
+					let key = parser.string()?;
+					parser.expect(b':')?;
+					match key.as_str() {
+						"x" => x = Some(parser.number()?),
+						"y" => y = Some(parser.number()?),
+						_ => parser.skip_value()?,
+					}
+					Ok(())
+				})?;
+			},
+			_ => return Err(self.error("expected a point")),
+		}
+
+		match (x, y) {
+			(Some(x), Some(y)) => Ok(Point{x, y}),
+			_ => Err(self.error("expected a point with two coordinates")),
+		}
+	}
+}
+
+
+//-----------------------------------------------------------------------------
+// Output
+//-----------------------------------------------------------------------------
+
+fn write_circle<W: Write>(output: &mut W, circle: &ClosedCircle, format: Format) -> io::Result<()> {
+	let (x, y, radius) = (circle.center.x, circle.center.y, circle.radius());
+	match format {
+		Format::Text => writeln!(output, "center: {} radius: {}", circle.center, radius),
+		Format::Csv => writeln!(output, "{},{},{}", x, y, radius),
+		Format::Json => writeln!(output, "{{\"x\": {}, \"y\": {}, \"radius\": {}}}", x, y, radius),
+	}
+}
+
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn args(text: &str) -> impl Iterator<Item=String> + '_ {
+		text.split_whitespace().map(String::from)
+	}
+
+	fn points(text: &str, format: Format) -> Result<Vec<Point>, String> {
+		read_points(text.as_bytes(), format)?.collect()
+	}
+
+	#[test]
+	fn parse_args_() {
+		assert_eq!(parse_args(args("-f csv --output json -w 10 in.txt")), Ok(Some(Options{
+			input_format: Some(Format::Csv),
+			output_format: Format::Json,
+			window: Some(10),
+			path: Some("in.txt".to_string()),
+		})));
+		assert_eq!(parse_args(args("--help")), Ok(None));
+		assert!(parse_args(args("--window 0")).is_err());
+		assert!(parse_args(args("--format")).is_err());
+		assert!(parse_args(args("--format xml")).is_err());
+		assert!(parse_args(args("a.txt b.txt")).is_err());
+	}
+
+	#[test]
+	fn format_from_extension_() {
+		assert_eq!(format_from_extension("points.CSV"), Some(Format::Csv));
+		assert_eq!(format_from_extension("dir/points.json"), Some(Format::Json));
+		assert_eq!(format_from_extension("points"), None);
+	}
+
+	#[test]
+	fn text_input() {
+		assert_eq!(
+			points("1 2\n3\n  4 -5e-1 6\n\n", Format::Text),
+			Ok(vec!(Point{x: 1., y: 2.}, Point{x: 3., y: 4.}, Point{x: -0.5, y: 6.})),
+		);
+		assert_eq!(
+			points("1\n2 3\n\n", Format::Text),
+			Err("line 2: unpaired coordinate".to_string()),
+		);
+		assert_eq!(points("1 x", Format::Text), Err("line 1: invalid number 'x'".to_string()));
+	}
+
+	#[test]
+	fn csv_input() {
+		assert_eq!(
+			points("x,y\n1, 2\n\n3,4,extra\n", Format::Csv),
+			Ok(vec!(Point{x: 1., y: 2.}, Point{x: 3., y: 4.})),
+		);
+		assert_eq!(points("1,2\nx,y\n", Format::Csv), Err("line 2: invalid number 'x'".to_string()));
+		assert_eq!(points("1;2\n", Format::Csv), Err("line 1: expected 'x,y'".to_string()));
+	}
+
+	#[test]
+	fn json_input() {
+		assert_eq!(
+			points("[[1, 2], {\"id\": 7, \"y\": 4, \"x\": 3e0}]", Format::Json),
+			Ok(vec!(Point{x: 1., y: 2.}, Point{x: 3., y: 4.})),
+		);
+		assert_eq!(
+			points("[{\"x\": 1, \"note\": \"}\", \"y\": 2, \"more\": [{}, [], false]}]", Format::Json),
+			Ok(vec!(Point{x: 1., y: 2.})),
+		);
+		assert_eq!(points(" [ ] ", Format::Json), Ok(vec!()));
+		assert_eq!(points("[[1]]", Format::Json), Err("line 1: expected a point with two coordinates".to_string()));
+		assert_eq!(points("[\n[1, 2]\n", Format::Json), Err("line 3: expected ',' or ']'".to_string()));
+		assert_eq!(points("[[1, 2]] x", Format::Json), Err("line 1: trailing characters".to_string()));
+	}
+
+	#[test]
+	fn output() {
+		let circle = ClosedCircle::new(Point{x: 1., y: -2.}, 0.5);
+		let written = |format| {
+			let mut output = Vec::new();
+			write_circle(&mut output, &circle, format).unwrap();
+			String::from_utf8(output).unwrap()
+		};
+
+		assert_eq!(written(Format::Text), "center: (1, -2) radius: 0.5\n");
+		assert_eq!(written(Format::Csv), "1,-2,0.5\n");
+		assert_eq!(written(Format::Json), "{\"x\": 1, \"y\": -2, \"radius\": 0.5}\n");
+	}
 }
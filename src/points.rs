@@ -1,20 +1,25 @@
+use crate::scalar::Scalar;
+
+use std::cmp::Ordering;
 use std::fmt;
 use std::ops::*;
 
 
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
-pub struct Point{pub x: f64, pub y: f64}
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point<T = f64>{pub x: T, pub y: T}
 
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
-pub struct Vector{pub x: f64, pub y: f64}
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vector<T = f64>{pub x: T, pub y: T}
 
-impl fmt::Display for Point {
+impl<T: fmt::Display> fmt::Display for Point<T> {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(formatter, "({}, {})", self.x, self.y)
     }
 }
 
-impl fmt::Display for Vector {
+impl<T: fmt::Display> fmt::Display for Vector<T> {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(formatter, "<{}, {}>", self.x, self.y)
     }
@@ -31,6 +36,41 @@ impl Point {
 			y: f64::from_bits(bits.1),
 		}
 	}
+
+}
+
+impl<T: Scalar> Point<T> {
+	pub fn is_finite(self) -> bool {
+		self.x.is_finite() && self.y.is_finite()
+	}
+
+	pub fn to_f64(self) -> Point {
+		Point{x: self.x.to_f64(), y: self.y.to_f64()}
+	}
+
+	// Nearest point with the given scalar type
+	pub fn from_f64(p: Point) -> Self {
+		Self{x: T::from_f64(p.x), y: T::from_f64(p.y)}
+	}
+}
+
+// Total order on coordinates, with NaN after every number, so that sorting
+// points never panics
+pub(crate) fn cmp_coordinates<T: Scalar>(a: T, b: T) -> Ordering {
+	#[allow(clippy::eq_op)]
+	let is_nan = |c: T| c != c;
+	a.partial_cmp(&b).unwrap_or_else(|| is_nan(a).cmp(&is_nan(b)))
+}
+
+// Lexicographic order by x, then y
+pub(crate) fn cmp_lexicographic<T: Scalar>(p1: &Point<T>, p2: &Point<T>) -> Ordering {
+	cmp_coordinates(p1.x, p2.x).then(cmp_coordinates(p1.y, p2.y))
+}
+
+// Lexicographic order by x, then y, then z
+pub(crate) fn cmp_lexicographic3(p1: &Point3, p2: &Point3) -> Ordering {
+	cmp_lexicographic(&Point{x: p1.x, y: p1.y}, &Point{x: p2.x, y: p2.y})
+		.then(cmp_coordinates(p1.z, p2.z))
 }
 
 
@@ -43,10 +83,10 @@ impl Point {
 // cannot add two points
 
 // Point + Vector
-impl Add<Vector> for Point {
+impl<T: Scalar> Add<Vector<T>> for Point<T> {
 	type Output = Self;
 	
-	fn add(self, other: Vector) -> Self::Output {
+	fn add(self, other: Vector<T>) -> Self::Output {
 		Self{x: self.x + other.x, y: self.y + other.y}
 	}
 }
@@ -55,7 +95,7 @@ impl Add<Vector> for Point {
 // cannot add a point to a vector (only vice-versa)
 
 // Vector + Vector
-impl Add<Vector> for Vector {
+impl<T: Scalar> Add<Vector<T>> for Vector<T> {
 	type Output = Self;
 	
 	fn add(self, other: Self) -> Self::Output {
@@ -69,8 +109,8 @@ impl Add<Vector> for Vector {
 //-----------------------------------------------------------------------------
 
 // Point - Point
-impl Sub<Point> for Point {
-	type Output = Vector;
+impl<T: Scalar> Sub<Point<T>> for Point<T> {
+	type Output = Vector<T>;
 	
 	fn sub(self, other: Self) -> Self::Output {
 		Vector{x: self.x - other.x, y: self.y - other.y}
@@ -78,11 +118,11 @@ impl Sub<Point> for Point {
 }
 
 // Point - Vector
-impl Sub<Vector> for Point {
-	type Output = Point;
+impl<T: Scalar> Sub<Vector<T>> for Point<T> {
+	type Output = Self;
 	
-	fn sub(self, other: Vector) -> Self::Output {
-		Point{x: self.x - other.x, y: self.y - other.y}
+	fn sub(self, other: Vector<T>) -> Self::Output {
+		Self{x: self.x - other.x, y: self.y - other.y}
 	}
 }
 
@@ -90,7 +130,7 @@ impl Sub<Vector> for Point {
 // cannot subtract a point from a vector
 
 // Vector - Vector
-impl Sub<Vector> for Vector {
+impl<T: Scalar> Sub<Vector<T>> for Vector<T> {
 	type Output = Self;
 	
 	fn sub(self, other: Self) -> Self::Output {
@@ -103,10 +143,10 @@ impl Sub<Vector> for Vector {
 // Multiplications
 //-----------------------------------------------------------------------------
 
-impl Mul<f64> for Vector {
+impl<T: Scalar> Mul<T> for Vector<T> {
 	type Output = Self;
 	
-	fn mul(self, other: f64) -> Self::Output {
+	fn mul(self, other: T) -> Self::Output {
 		Self{x: self.x * other, y: self.y * other}
 	}
 }
@@ -115,22 +155,27 @@ impl Mul<f64> for Vector {
 // Vector Operations
 //-----------------------------------------------------------------------------
 
-impl Vector {
-	pub fn dot(self, other: Self) -> f64 {
+// Computed in the scalar type, and so may overflow for integers; distances
+// between points are better found with `Scalar::sq_distance`
+impl<T: Scalar> Vector<T> {
+	pub fn dot(self, other: Self) -> T {
 		self.x*other.x + self.y*other.y
 	}
 
-	pub fn sq_mag(&self) -> f64 {
+	pub fn sq_mag(&self) -> T {
 		self.dot(*self)
 	}
 
-	pub fn cross(self, other: Self) -> f64 {
+	pub fn cross(self, other: Self) -> T {
 		self.x*other.y - self.y*other.x	
 	}
 
-	pub fn normal(self) -> Vector {
-		Vector{x: -self.y, y: self.x}
+	pub fn normal(self) -> Self {
+		Self{x: -self.y, y: self.x}
 	}
+}
+
+impl Vector {
 
 	pub fn rotated(self, angle: f64) -> Vector {
 		Vector{
@@ -144,11 +189,126 @@ impl Vector {
 	}
 }
 
+
+//-----------------------------------------------------------------------------
+// Three Dimensions
+//-----------------------------------------------------------------------------
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point3{pub x: f64, pub y: f64, pub z: f64}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vector3{pub x: f64, pub y: f64, pub z: f64}
+
+impl fmt::Display for Point3 {
+	fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(formatter, "({}, {}, {})", self.x, self.y, self.z)
+	}
+}
+
+impl fmt::Display for Vector3 {
+	fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(formatter, "<{}, {}, {}>", self.x, self.y, self.z)
+	}
+}
+
+impl Point3 {
+	pub fn to_bits(self) -> (u64, u64, u64) {
+		(self.x.to_bits(), self.y.to_bits(), self.z.to_bits())
+	}
+
+	pub fn from_bits(bits: (u64, u64, u64)) -> Self {
+		Self{
+			x: f64::from_bits(bits.0),
+			y: f64::from_bits(bits.1),
+			z: f64::from_bits(bits.2),
+		}
+	}
+
+	pub fn is_finite(self) -> bool {
+		self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
+	}
+}
+
+// Point3 + Vector3
+impl Add<Vector3> for Point3 {
+	type Output = Self;
+
+	fn add(self, other: Vector3) -> Self::Output {
+		Self{x: self.x + other.x, y: self.y + other.y, z: self.z + other.z}
+	}
+}
+
+// Vector3 + Vector3
+impl Add<Vector3> for Vector3 {
+	type Output = Self;
+
+	fn add(self, other: Self) -> Self::Output {
+		Self{x: self.x + other.x, y: self.y + other.y, z: self.z + other.z}
+	}
+}
+
+// Point3 - Point3
+impl Sub<Point3> for Point3 {
+	type Output = Vector3;
+
+	fn sub(self, other: Self) -> Self::Output {
+		Vector3{x: self.x - other.x, y: self.y - other.y, z: self.z - other.z}
+	}
+}
+
+// Point3 - Vector3
+impl Sub<Vector3> for Point3 {
+	type Output = Self;
+
+	fn sub(self, other: Vector3) -> Self::Output {
+		Self{x: self.x - other.x, y: self.y - other.y, z: self.z - other.z}
+	}
+}
+
+// Vector3 - Vector3
+impl Sub<Vector3> for Vector3 {
+	type Output = Self;
+
+	fn sub(self, other: Self) -> Self::Output {
+		Self{x: self.x - other.x, y: self.y - other.y, z: self.z - other.z}
+	}
+}
+
+impl Mul<f64> for Vector3 {
+	type Output = Self;
+
+	fn mul(self, other: f64) -> Self::Output {
+		Self{x: self.x * other, y: self.y * other, z: self.z * other}
+	}
+}
+
+impl Vector3 {
+	pub fn dot(self, other: Self) -> f64 {
+		self.x*other.x + self.y*other.y + self.z*other.z
+	}
+
+	pub fn sq_mag(&self) -> f64 {
+		self.dot(*self)
+	}
+
+	pub fn cross(self, other: Self) -> Self {
+		Self{
+			x: self.y*other.z - self.z*other.y,
+			y: self.z*other.x - self.x*other.z,
+			z: self.x*other.y - self.y*other.x,
+		}
+	}
+}
+
 ///////////////////////////////////////////////////////////////////////////////
 
 #[cfg(test)]
 mod tests {
-	use super::{Point, Vector};
+	use super::{Point, Vector, Point3, Vector3, cmp_lexicographic};
+	use std::cmp::Ordering;
 
     #[test]
     fn make_point() {
@@ -160,6 +320,31 @@ mod tests {
 		let _v = Vector{x: 1.0, y: 2.0};
     }
 
+    #[test]
+    fn is_finite() {
+		assert!(Point{x: 1.0, y: -2.0}.is_finite());
+		assert!(!Point{x: f64::NAN, y: 0.}.is_finite());
+		assert!(!Point{x: 0., y: f64::NEG_INFINITY}.is_finite());
+    }
+
+    #[test]
+    fn convert_scalar() {
+		let p = Point{x: 1.5_f32, y: -2.};
+		assert_eq!(p.to_f64(), Point{x: 1.5, y: -2.});
+		assert_eq!(Point::<i32>::from_f64(Point{x: 1.4, y: -2.6}), Point{x: 1, y: -3});
+		assert!(Point{x: i64::MAX, y: 0}.is_finite());
+    }
+
+    #[test]
+    fn order_lexicographic() {
+		let nan = Point{x: f64::NAN, y: 0.};
+		let mut points = [nan, Point{x: 1., y: 2.}, Point{x: f64::INFINITY, y: 0.}, Point{x: 1., y: -1.}];
+		points.sort_by(cmp_lexicographic);
+		assert_eq!(&points[..3], &[Point{x: 1., y: -1.}, Point{x: 1., y: 2.}, Point{x: f64::INFINITY, y: 0.}]);
+		assert!(points[3].x.is_nan());
+		assert_eq!(cmp_lexicographic(&nan, &nan), Ordering::Equal);
+    }
+
     #[test]
     fn convert_through_bits() {
 		let p = Point{x: 1.0, y: -2.0};
@@ -220,6 +405,7 @@ mod tests {
 	fn v_sq_mag() {
 		let v = Vector{x: 1., y: 2.};
 		assert_eq!(v.sq_mag(), 5.);
+		assert_eq!(Vector{x: -3_i64, y: 4}.sq_mag(), 25);
 	}
 
 	#[test]
@@ -255,4 +441,29 @@ mod tests {
 		assert_eq!(v.rotated_quantage(0.75), Vector{x: 1.9999999999999998, y: -1.0000000000000004});
 		assert_eq!(v.rotated_quantage(1.), Vector{x: 1.0000000000000004, y: 1.9999999999999998});
 	}
+
+	//-------------------------------------------------------------------------
+
+	#[test]
+	fn point3_ops() {
+		let p = Point3{x: 1., y: 2., z: 3.};
+		let v = Vector3{x: 3., y: 5., z: -1.};
+		assert_eq!(p+v, Point3{x: 4., y: 7., z: 2.});
+		assert_eq!(p-v, Point3{x: -2., y: -3., z: 4.});
+		assert_eq!((p+v)-p, v);
+		assert_eq!(v+v-v, v);
+		assert_eq!(v*2., Vector3{x: 6., y: 10., z: -2.});
+		assert_eq!(p, Point3::from_bits(p.to_bits()));
+		assert!(!Point3{x: 0., y: 0., z: f64::NAN}.is_finite());
+	}
+
+	#[test]
+	fn v3_products() {
+		let v1 = Vector3{x: 1., y: 0., z: 0.};
+		let v2 = Vector3{x: 0., y: 2., z: 0.};
+		assert_eq!(v1.cross(v2), Vector3{x: 0., y: 0., z: 2.});
+		assert_eq!(v2.cross(v1), Vector3{x: 0., y: 0., z: -2.});
+		assert_eq!(v1.dot(v2), 0.);
+		assert_eq!(Vector3{x: 1., y: 2., z: 2.}.sq_mag(), 9.);
+	}
 }
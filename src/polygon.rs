@@ -1,54 +1,82 @@
-use crate::points::{Point, Vector};
+// Kept in the style the polygon code was first written in
+#![allow(clippy::filter_next, clippy::needless_range_loop)]
 
+use crate::points::{Point, Vector, cmp_lexicographic};
+use crate::scalar::Scalar;
+use crate::error::Error;
+
+use std::cmp::Ordering;
+use std::convert::TryFrom;
 use std::vec::Vec;
 
 
-#[derive(Debug, PartialEq)]
-pub struct PolygonVertex<'a>{
-	vertices: &'a Vec<Point>,
+// Vertex of a `ConvexPolygon`, from which to walk along its boundary
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PolygonVertex<'a, T = f64>{
+	vertices: &'a [Point<T>],
 	index: usize,
 }
 
-#[derive(Debug, PartialEq)]
-pub struct PolygonEdge(Point, Point);
-
-#[derive(Debug, PartialEq)]
-pub struct ConvexPolygon{
-	vertices: Vec<Point>,
+// Directed edge from its start to its end point; the polygon interior lies
+// to its left
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PolygonEdge<T = f64>(pub Point<T>, pub Point<T>);
+
+// Convex polygon, with its vertices in counter-clockwise order. Polygons of
+// fewer than three vertices are degenerate, and cover only their vertices
+// (or the segment between them). With the `serde` feature, it is
+// (de)serialized as its list of vertices, which must pass
+// `try_from_positions`.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(
+	try_from = "Vec<Point<T>>",
+	bound(deserialize = "T: Scalar + serde::Deserialize<'de>"),
+))]
+pub struct ConvexPolygon<T = f64>{
+	vertices: Vec<Point<T>>,
 }
 
-#[derive(Debug, PartialEq)]
-enum EdgeRegion {
+// Side of an edge's line that a point lies on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EdgeRegion {
 	Interior,
 	Boundary,
 	Exterior,
 }
 
 
-impl PolygonEdge {
-	fn direction(&self) -> Vector {
+impl<T: Scalar> PolygonEdge<T> {
+	pub fn start(&self) -> Point<T> {
+		self.0
+	}
+
+	pub fn end(&self) -> Point<T> {
+		self.1
+	}
+
+	pub fn direction(&self) -> Vector<T> {
 		self.1 - self.0
 	}
 
-	fn region(&self, point: Point) -> EdgeRegion {
-		use std::cmp::Ordering::*;
+	// Decided exactly; points with NaN coordinates lie on no side
+	pub fn region(&self, point: Point<T>) -> EdgeRegion {
+		use Ordering::*;
 		use EdgeRegion::*;
 
-		match self.direction()
-			.normal()
-			.dot(point-self.0)
-			.partial_cmp(&0.).unwrap()
-		{
-			Less => Exterior,
-			Equal => Boundary,
-			Greater => Interior,
+		match T::orient2d(self.0, self.1, point) {
+			Some(Less) => Exterior,
+			Some(Equal) | None => Boundary,
+			Some(Greater) => Interior,
 		}
 	}
 }
 
 
-impl PolygonVertex<'_> {
-	pub fn position(&self) -> Point {
+impl<T: Scalar> PolygonVertex<'_, T> {
+	pub fn position(&self) -> Point<T> {
 		self.vertices[self.index]
 	}
 
@@ -66,20 +94,21 @@ impl PolygonVertex<'_> {
 		}
 	}
 
-	pub fn fwd_edge(&self) -> PolygonEdge {
+	pub fn fwd_edge(&self) -> PolygonEdge<T> {
 		PolygonEdge(
 			self.vertices[self.index],
 			self.vertices[(self.index+1) % self.vertices.len()]
 		)
 	}
 
-	pub fn rev_edge(&self) -> PolygonEdge {
+	pub fn rev_edge(&self) -> PolygonEdge<T> {
 		PolygonEdge(
 			self.vertices[(self.index+self.vertices.len()-1) % self.vertices.len()],
 			self.vertices[self.index]
 		)
 	}
 
+	// Position of the vertex in `ConvexPolygon::positions`
 	pub fn to_id(self) -> usize {
 		self.index
 	}
@@ -87,37 +116,164 @@ impl PolygonVertex<'_> {
 
 
 impl ConvexPolygon {
+	// Empty polygon over f64 coordinates; for other scalar types, use
+	// `default`
 	pub fn new() -> Self {
-		Self{vertices: Vec::new()}
+		Self::default()
+	}
+}
+
+impl<T: Scalar> TryFrom<Vec<Point<T>>> for ConvexPolygon<T> {
+	type Error = Error;
+
+	fn try_from(positions: Vec<Point<T>>) -> Result<Self, Error> {
+		Self::try_from_positions(positions).ok_or(Error::InvalidSnapshot(
+			"polygon vertices must be convex and counter-clockwise"
+		))
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for ConvexPolygon<T> {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serde::Serialize::serialize(&self.vertices, serializer)
+	}
+}
+
+impl<T: Scalar> ConvexPolygon<T> {
+	// Polygon with the given vertices, if they are in convex position and
+	// counter-clockwise order, with no three collinear
+	pub fn try_from_positions(positions: Vec<Point<T>>) -> Option<Self> {
+		Some(Self{vertices: positions}).filter(|polygon| polygon.is_convex())
+	}
+
+	// Convex hull of the points, counter-clockwise from the lexicographically
+	// smallest vertex (Andrew's monotone chain); the same polygon as inserting
+	// them one by one, in O(n log n) time however many are vertices
+	pub fn hull_of(points: &[Point<T>]) -> Self {
+		let mut sorted = points.to_vec();
+		sorted.sort_by(cmp_lexicographic);
+		sorted.dedup();
+		if sorted.len() <= 2 {
+			return Self{vertices: sorted};
+		}
+
+		let chain = |points: &mut dyn Iterator<Item=&Point<T>>| {
+			let mut chain: Vec<Point<T>> = Vec::new();
+			for p in points {
+				while chain.len() >= 2
+					&& T::orient2d(chain[chain.len()-2], chain[chain.len()-1], *p) != Some(Ordering::Greater)
+				{
+					chain.pop();
+				}
+				chain.push(*p);
+			}
+			// Ends where the other chain starts
+			chain.pop();
+			chain
+		};
+		let mut vertices = chain(&mut sorted.iter());
+		vertices.extend(chain(&mut sorted.iter().rev()));
+		Self{vertices}
+	}
+
+	// Whether the vertices are finite, distinct, and in convex position and
+	// counter-clockwise order with no three collinear, as every operation
+	// keeps them
+	pub fn is_convex(&self) -> bool {
+		let (vertices, n) = (&self.vertices, self.vertices.len());
+		if !vertices.iter().all(|p| p.is_finite()) {
+			return false;
+		}
+		if n <= 2 {
+			return n < 2 || vertices[0] != vertices[1];
+		}
+
+		// Every turn is to the left, and the vertices advance lexicographically
+		// from one extreme to the other and back only once, so that the
+		// boundary winds around just once
+		let turns_left = (0..n).all(|i| {
+			T::orient2d(vertices[i], vertices[(i+1)%n], vertices[(i+2)%n]) == Some(Ordering::Greater)
+		});
+		let advances = |i: usize| {
+			cmp_lexicographic(&vertices[i], &vertices[(i+1)%n]) == Ordering::Less
+		};
+		let reversals = (0..n).filter(|i| advances(*i) != advances((i+1)%n)).count();
+		turns_left && reversals == 2
 	}
 
 	pub fn degree(&self) -> usize {
 		self.vertices.len()
 	}
 
-	fn vertex(&self, index: usize) -> PolygonVertex {
+	pub fn is_empty(&self) -> bool {
+		self.vertices.is_empty()
+	}
+
+	fn vertex(&self, index: usize) -> PolygonVertex<'_, T> {
 		PolygonVertex {
 			vertices: &self.vertices,
 			index,
 		}
 	}
 
-	pub fn some_vertex(&self) -> PolygonVertex {
-		self.vertex(0_usize)
+	pub fn some_vertex(&self) -> Option<PolygonVertex<'_, T>> {
+		if self.is_empty() {
+			None
+		} else {
+			Some(self.vertex(0_usize))
+		}
+	}
+
+	// Vertex positions, in counter-clockwise order
+	pub fn positions(&self) -> &[Point<T>] {
+		&self.vertices
 	}
 
-	fn exterior_witness(&self, point: Point) -> Option<PolygonVertex> {
+	pub fn vertices(&self) -> impl Iterator<Item=PolygonVertex<'_, T>> + '_ {
+		(0..self.vertices.len()).map(move |i| self.vertex(i))
+	}
+
+	// Forward edges of each vertex in turn; a degenerate polygon has an edge
+	// each way between its two vertices, or a single zero-length edge
+	pub fn edges(&self) -> impl Iterator<Item=PolygonEdge<T>> + '_ {
+		self.vertices().map(|v| v.fwd_edge())
+	}
+
+	fn exterior_witness(&self, point: Point<T>) -> Option<PolygonVertex<'_, T>> {
 		(0..self.vertices.len())
 			.map(|i| self.vertex(i))
 			.filter(|v| v.fwd_edge().region(point) == EdgeRegion::Exterior)
 			.next()
 	}
 
-	pub fn covers(&self, point: Point) -> bool {
+	// A degenerate (two-vertex) polygon has no exterior edge for points that
+	// extend it along its own line; returns the vertex such a point replaces
+	fn collinear_witness(&self, point: Point<T>) -> Option<usize> {
+		if self.degree() != 2 {
+			return None;
+		}
+		let edge = self.vertex(0).fwd_edge();
+		if edge.region(point) != EdgeRegion::Boundary {
+			None
+		} else if T::dot2d(edge.0, point, edge.1) == Some(Ordering::Less) {
+			Some(0)
+		} else if T::dot2d(edge.1, point, edge.0) == Some(Ordering::Less) {
+			Some(1)
+		} else {
+			None
+		}
+	}
+
+	pub fn covers(&self, point: Point<T>) -> bool {
+		if self.degree() <= 1 {
+			return self.find(point).is_some();
+		}
 		self.exterior_witness(point).is_none()
+			&& self.collinear_witness(point).is_none()
 	}
 
-	pub fn find(&self, point: Point) -> Option<PolygonVertex> {
+	pub fn find(&self, point: Point<T>) -> Option<PolygonVertex<'_, T>> {
 		Some(self.vertex(
 			(0..self.vertices.len())
 			.filter(|i| self.vertices[*i] == point)
@@ -125,18 +281,31 @@ impl ConvexPolygon {
 		))
 	}
 
-	pub fn find_best(&self, objective: Vector) -> PolygonVertex {
+	// Vertex furthest along the objective direction
+	pub fn find_best(&self, objective: Vector<T>) -> Option<PolygonVertex<'_, T>> {
 		(0..self.vertices.len())
 			.map(|i| self.vertex(i))
 			.filter(|v|
-				objective.dot(v.fwd_edge().direction()) <= 0.
-				&& objective.dot(v.rev_edge().direction()) >= 0.
+				objective.dot(v.fwd_edge().direction()) <= T::default()
+				&& objective.dot(v.rev_edge().direction()) >= T::default()
 			)
-			.next().unwrap()
+			.next()
+	}
+
+	// As `try_insert`, for points known to be finite
+	pub(crate) fn insert(&mut self, new_point: Point<T>) -> Vec<Point<T>> {
+		self.try_insert(new_point).unwrap_or_else(|e| panic!("{}", e))
 	}
 
-	pub fn insert(&mut self, new_point: Point) -> Vec<Point> {
-		if let Some(vertex) = self.exterior_witness(new_point) {
+	// Returns the points left out of the polygon: the vertices the new point
+	// obviated, or else the new point itself if it is already covered. Fails
+	// on points with non-finite coordinates, leaving the polygon unchanged.
+	pub fn try_insert(&mut self, new_point: Point<T>) -> Result<Vec<Point<T>>, Error> {
+		if !new_point.is_finite() {
+			return Err(Error::NonFinitePoint(new_point.to_f64()));
+		}
+
+		Ok(if let Some(vertex) = self.exterior_witness(new_point) {
 			let n = self.vertices.len();
 
 			let v0_idx = (0..n)
@@ -145,18 +314,19 @@ impl ConvexPolygon {
 					self.vertex(*j).rev_edge().region(new_point)
 					== EdgeRegion::Interior
 				})
-				.next().unwrap();
+				.next().ok_or(Error::InconsistentState("no polygon edge faces the point"))?;
 			let v1_idx = (0..n)
 				.map(|j| (vertex.index+1+j)%n)
 				.filter(|j| {
 					self.vertex(*j).fwd_edge().region(new_point)
 					== EdgeRegion::Interior
 				})
-				.next().unwrap();
-
-			assert!(v0_idx != v1_idx);
+				.next().ok_or(Error::InconsistentState("no polygon edge faces the point"))?;
+			if v0_idx == v1_idx {
+				return Err(Error::InconsistentState("point is exterior to every polygon edge"));
+			}
 
-			let mut removed_vertices = Vec::<Point>::new();
+			let mut removed_vertices = Vec::<Point<T>>::new();
 			if v0_idx < v1_idx {
 				removed_vertices.extend(self.vertices.drain(v0_idx+1..v1_idx));
 				self.vertices.insert(v0_idx+1, new_point);
@@ -167,25 +337,52 @@ impl ConvexPolygon {
 			}
 
 			removed_vertices
+		} else if let Some(index) = self.collinear_witness(new_point) {
+			vec!(std::mem::replace(&mut self.vertices[index], new_point))
 		} else if self.degree() <= 1 && self.find(new_point).is_none() {
 			self.vertices.push(new_point);
 			vec!()
 		} else {
 			vec!(new_point)
+		})
+	}
+
+	// Reverts `insert(new_point)`, given the vertices it removed; insertions
+	// must be undone in the reverse order they were made
+	pub(crate) fn undo_insert(&mut self, new_point: Point<T>, removed_vertices: Vec<Point<T>>) {
+		if removed_vertices[..] == [new_point] {
+			return;
 		}
+		// Reinserting the removed vertices in place of the new point restores
+		// the original polygon, up to a rotation of its vertex order
+		let index = self.find(new_point).unwrap().to_id();
+		self.vertices.splice(index..=index, removed_vertices);
 	}
 
-	pub fn remove(&mut self, index: usize) -> Point {
+	// Removes the vertex at the given position, leaving the (still convex)
+	// polygon of the remaining vertices
+	pub fn remove(&mut self, index: usize) -> Point<T> {
 		self.vertices.remove(index)
 	}
 }
 
+// Panics on points with non-finite coordinates
+impl<T: Scalar> std::iter::FromIterator<Point<T>> for ConvexPolygon<T> {
+	fn from_iter<I: IntoIterator<Item=Point<T>>>(iter: I) -> Self {
+		let mut polygon = Self::default();
+		for p in iter {
+			polygon.insert(p);
+		}
+		polygon
+	}
+}
+
 
 ///////////////////////////////////////////////////////////////////////////////
 
 #[cfg(test)]
 mod tests {
-	use super::{ConvexPolygon, PolygonVertex, PolygonEdge, Point, Vector};
+	use super::{ConvexPolygon, PolygonVertex, PolygonEdge, EdgeRegion, Error, Point, Vector, cmp_lexicographic};
 
 	fn convex_polygon() -> ConvexPolygon {
 		let mut cp = ConvexPolygon::new();
@@ -264,6 +461,33 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn edges() {
+		let cp = convex_polygon();
+		let start = start_vertex(&cp).to_id();
+		let ordered_vertices = ordered_vertices();
+
+		let edges: Vec<PolygonEdge> = cp.edges().collect();
+		assert_eq!(edges.len(), 4);
+		for i in 0..4 {
+			let edge = edges[(start+i) % 4];
+			assert_eq!(edge.start(), ordered_vertices[i]);
+			assert_eq!(edge.end(), ordered_vertices[(i+1) % 4]);
+			assert_eq!(edge.region(Point{x: 0.5, y: 0.}), EdgeRegion::Interior);
+		}
+	}
+
+	#[test]
+	fn from_iter() {
+		let cp: ConvexPolygon = ordered_vertices().into_iter()
+			.chain(std::iter::once(Point{x: 0., y: 0.}))
+			.collect();
+		assert_eq!(cp.degree(), 4);
+		assert_eq!(cp.vertices().count(), 4);
+		assert!(cp.some_vertex().is_some());
+		assert!(ConvexPolygon::new().some_vertex().is_none());
+	}
+
 	#[test]
 	fn covers() {
 		let cp = convex_polygon();
@@ -271,6 +495,13 @@ mod tests {
 		assert!(cp.covers(Point{x: 1., y: 0.}));
 		assert!(cp.covers(Point{x: 1., y: 0.5}));
 		assert!(!cp.covers(Point{x: 1., y: 1.}));
+
+		// Degenerate polygons cover only their vertices
+		let mut cp = ConvexPolygon::new();
+		assert!(!cp.covers(Point{x: 1., y: 0.}));
+		cp.insert(Point{x: 1., y: 0.});
+		assert!(cp.covers(Point{x: 1., y: 0.}));
+		assert!(!cp.covers(Point{x: 1., y: 1.}));
 	}
 
 	#[test]
@@ -294,17 +525,18 @@ mod tests {
 	fn find_best() {
 		let cp = convex_polygon();
 		assert_eq!(
-			cp.find_best(Vector{x: 2., y: 0.}).position(),
+			cp.find_best(Vector{x: 2., y: 0.}).unwrap().position(),
 			Point{x: 2., y: 0.},
 		);
 		assert_eq!(
-			cp.find_best(Vector{x: 1., y: 0.}).position(),
+			cp.find_best(Vector{x: 1., y: 0.}).unwrap().position(),
 			Point{x: 2., y: 0.},
 		);
 		assert_eq!(
-			cp.find_best(Vector{x: 3., y: 1.}).position(),
+			cp.find_best(Vector{x: 3., y: 1.}).unwrap().position(),
 			Point{x: 2., y: 0.},
 		);
+		assert_eq!(ConvexPolygon::new().find_best(Vector{x: 1., y: 0.}), None);
 	}
 
 	#[test]
@@ -345,6 +577,77 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn try_insert() {
+		let mut cp = convex_polygon();
+		let point = Point{x: f64::INFINITY, y: 0.};
+		assert_eq!(cp.try_insert(point), Err(Error::NonFinitePoint(point)));
+		assert_eq!(cp, convex_polygon());
+		assert_eq!(cp.try_insert(Point{x: 0., y: 0.}), Ok(vec!(Point{x: 0., y: 0.})));
+	}
+
+	#[test]
+	fn insert_collinear() {
+		let mut cp = ConvexPolygon::new();
+		cp.insert(Point{x: 0., y: 0.});
+		cp.insert(Point{x: 1., y: 1.});
+
+		// Add point within the segment
+		assert!(cp.covers(Point{x: 0.5, y: 0.5}));
+		assert_eq!(cp.insert(Point{x: 0.5, y: 0.5}), vec!(Point{x: 0.5, y: 0.5}));
+		// Add points extending the segment at either end
+		assert!(!cp.covers(Point{x: 2., y: 2.}));
+		assert_eq!(cp.insert(Point{x: 2., y: 2.}), vec!(Point{x: 1., y: 1.}));
+		assert!(!cp.covers(Point{x: -1., y: -1.}));
+		assert_eq!(cp.insert(Point{x: -1., y: -1.}), vec!(Point{x: 0., y: 0.}));
+		assert_eq!(cp.degree(), 2);
+
+		// Add point off the segment
+		assert!(!cp.covers(Point{x: 0., y: 1.}));
+		assert_eq!(cp.insert(Point{x: 0., y: 1.}), vec!());
+		assert_eq!(cp.degree(), 3);
+	}
+
+	#[test]
+	fn insert_nearly_collinear() {
+		// Coordinates rounded off the line y = 3x - 0.2 by differing amounts
+		let points: Vec<Point> = [4, 0, 9, 2, 7, 1, 8, 3, 6, 5].iter()
+			.map(|i| Point{x: 0.1 * *i as f64, y: 0.3 * *i as f64 - 0.2})
+			.collect();
+
+		let mut cp = ConvexPolygon::new();
+		for (i, point) in points.iter().enumerate() {
+			cp.insert(*point);
+			assert!(points[..=i].iter().all(|p| cp.covers(*p)));
+		}
+	}
+
+	#[test]
+	fn undo_insert() {
+		let mut cp = convex_polygon();
+		let points = [
+			Point{x: 0., y: 0.},
+			Point{x: 0., y: 2.},
+			Point{x: 2., y: 2.},
+			Point{x: 3., y: -3.},
+		];
+
+		let mut removals = Vec::new();
+		for point in points.iter() {
+			removals.push(cp.insert(*point));
+		}
+		for point in points.iter().rev() {
+			cp.undo_insert(*point, removals.pop().unwrap());
+		}
+
+		assert_eq!(cp.degree(), 4);
+		let mut vertex = start_vertex(&cp);
+		for point in ordered_vertices().iter() {
+			assert_eq!(vertex.position(), *point);
+			vertex = vertex.fwd_vertex();
+		}
+	}
+
 	#[test]
 	fn remove() {
 		let mut cp = convex_polygon();
@@ -354,4 +657,54 @@ mod tests {
 		let vertex_id = vertex.to_id();
 		assert_eq!(cp.remove(vertex_id), ordered_vertices[0]);
 	}
+
+	#[test]
+	fn is_convex() {
+		let cp = convex_polygon();
+		assert!(cp.is_convex());
+		assert_eq!(ConvexPolygon::try_from_positions(cp.positions().to_vec()), Some(cp.clone()));
+
+		let mut clockwise = cp.positions().to_vec();
+		clockwise.reverse();
+		assert_eq!(ConvexPolygon::try_from_positions(clockwise), None);
+
+		// Left turns throughout, but winding around twice
+		let pentagram: Vec<Point> = (0..5)
+			.map(|i| (i*2 % 5) as f64 * 72_f64.to_radians())
+			.map(|theta| Point{x: theta.cos(), y: theta.sin()})
+			.collect();
+		assert_eq!(ConvexPolygon::try_from_positions(pentagram), None);
+
+		let collinear = vec!(Point{x: 0., y: 0.}, Point{x: 1., y: 0.}, Point{x: 2., y: 0.}, Point{x: 1., y: 1.});
+		assert_eq!(ConvexPolygon::try_from_positions(collinear), None);
+		assert_eq!(ConvexPolygon::try_from_positions(vec!(Point{x: 1., y: 0.}; 2)), None);
+		assert!(ConvexPolygon::try_from_positions(vec!(Point{x: 1., y: 0.})).is_some());
+		assert!(ConvexPolygon::<f64>::try_from_positions(vec!()).is_some());
+	}
+
+	#[test]
+	fn hull_of() {
+		let sorted = |polygon: &ConvexPolygon| {
+			let mut vertices = polygon.positions().to_vec();
+			vertices.sort_by(cmp_lexicographic);
+			vertices
+		};
+
+		// Scattered over a small grid, with duplicates and collinearities
+		let points: Vec<Point> = (0..60_u64)
+			.map(|i| (i*37 + 11) % 49)
+			.map(|i| Point{x: (i % 7) as f64, y: ((i*3) % 7) as f64 - (i / 7) as f64})
+			.collect();
+		let hull = ConvexPolygon::hull_of(&points);
+		let inserted: ConvexPolygon = points.iter().cloned().collect();
+		assert!(hull.is_convex());
+		assert_eq!(sorted(&hull), sorted(&inserted));
+		assert_eq!(hull.positions()[0], sorted(&hull)[0]);
+
+		let collinear = [Point{x: 1., y: 1.}, Point{x: 0., y: 0.}, Point{x: 3., y: 3.}, Point{x: 2., y: 2.}];
+		assert_eq!(ConvexPolygon::hull_of(&collinear).positions(), &[Point{x: 0., y: 0.}, Point{x: 3., y: 3.}]);
+		let same = [Point{x: 1., y: 2.}; 3];
+		assert_eq!(ConvexPolygon::hull_of(&same).positions(), &[Point{x: 1., y: 2.}]);
+		assert!(ConvexPolygon::<f64>::hull_of(&[]).is_empty());
+	}
 }
@@ -0,0 +1,370 @@
+use crate::points::{Point, Point3};
+
+
+// Geometric predicates whose signs are exact for all finite inputs (barring
+// underflow), after Shewchuk's "Adaptive Precision Floating-Point Arithmetic
+// and Fast Robust Geometric Predicates" (1997).
+//
+// Each predicate first evaluates its determinant in plain floating point, and
+// returns that whenever an error bound proves its sign correct; otherwise it
+// falls back to exact arithmetic on floating-point expansions. The magnitude
+// of a result is only approximate; its sign is exact. A NaN input yields NaN.
+
+// Half the machine epsilon, i.e. the unit roundoff of an f64
+const EPSILON: f64 = f64::EPSILON * 0.5;
+const ORIENT_ERROR_BOUND: f64 = (3. + 16.*EPSILON) * EPSILON;
+const INCIRCLE_ERROR_BOUND: f64 = (10. + 96.*EPSILON) * EPSILON;
+// Bound for plain dot products and sums of squares of differences, of up to
+// three terms
+const DOT_ERROR_BOUND: f64 = 4. * f64::EPSILON;
+
+
+// Positive if `a`, `b` and `c` are in counter-clockwise order, negative if
+// clockwise, and zero if collinear
+pub fn orient2d(a: Point, b: Point, c: Point) -> f64 {
+	let det_left = (a.x-c.x) * (b.y-c.y);
+	let det_right = (a.y-c.y) * (b.x-c.x);
+	let det = det_left - det_right;
+	if det.abs() >= ORIENT_ERROR_BOUND * (det_left.abs() + det_right.abs()) || det.is_nan() {
+		return det;
+	}
+
+	let (acx, bcx) = (Expansion::diff(a.x, c.x), Expansion::diff(b.x, c.x));
+	let (acy, bcy) = (Expansion::diff(a.y, c.y), Expansion::diff(b.y, c.y));
+	acx.mul(&bcy).sub(&acy.mul(&bcx)).estimate()
+}
+
+// Positive if `d` lies inside the circle through `a`, `b` and `c`, negative if
+// outside, and zero if on it, for `a`, `b` and `c` in counter-clockwise order;
+// the sign is reversed if they are clockwise
+pub fn incircle(a: Point, b: Point, c: Point, d: Point) -> f64 {
+	let (adx, ady) = (a.x-d.x, a.y-d.y);
+	let (bdx, bdy) = (b.x-d.x, b.y-d.y);
+	let (cdx, cdy) = (c.x-d.x, c.y-d.y);
+
+	let (bdxcdy, cdxbdy) = (bdx*cdy, cdx*bdy);
+	let (cdxady, adxcdy) = (cdx*ady, adx*cdy);
+	let (adxbdy, bdxady) = (adx*bdy, bdx*ady);
+	let alift = adx*adx + ady*ady;
+	let blift = bdx*bdx + bdy*bdy;
+	let clift = cdx*cdx + cdy*cdy;
+
+	let det = alift*(bdxcdy - cdxbdy) + blift*(cdxady - adxcdy) + clift*(adxbdy - bdxady);
+	let permanent = (bdxcdy.abs() + cdxbdy.abs()) * alift
+		+ (cdxady.abs() + adxcdy.abs()) * blift
+		+ (adxbdy.abs() + bdxady.abs()) * clift;
+	if det.abs() > INCIRCLE_ERROR_BOUND * permanent || det.is_nan() {
+		return det;
+	}
+
+	let (adx, ady) = (Expansion::diff(a.x, d.x), Expansion::diff(a.y, d.y));
+	let (bdx, bdy) = (Expansion::diff(b.x, d.x), Expansion::diff(b.y, d.y));
+	let (cdx, cdy) = (Expansion::diff(c.x, d.x), Expansion::diff(c.y, d.y));
+	let lift = |dx: &Expansion, dy: &Expansion| dx.mul(dx).add(&dy.mul(dy));
+	let cross = |x1: &Expansion, y1: &Expansion, x2: &Expansion, y2: &Expansion| {
+		x1.mul(y2).sub(&y1.mul(x2))
+	};
+
+	lift(&adx, &ady).mul(&cross(&bdx, &bdy, &cdx, &cdy))
+		.add(&lift(&bdx, &bdy).mul(&cross(&cdx, &cdy, &adx, &ady)))
+		.add(&lift(&cdx, &cdy).mul(&cross(&adx, &ady, &bdx, &bdy)))
+		.estimate()
+}
+
+// Dot product of `b - a` and `c - a`: negative if the angle at `a` is obtuse,
+// positive if acute, and zero if right (or degenerate)
+pub fn dot2d(a: Point, b: Point, c: Point) -> f64 {
+	let terms = ((b.x-a.x) * (c.x-a.x), (b.y-a.y) * (c.y-a.y));
+	let dot = terms.0 + terms.1;
+	if dot.abs() > DOT_ERROR_BOUND * (terms.0.abs() + terms.1.abs()) || dot.is_nan() {
+		return dot;
+	}
+
+	let (bax, bay) = (Expansion::diff(b.x, a.x), Expansion::diff(b.y, a.y));
+	let (cax, cay) = (Expansion::diff(c.x, a.x), Expansion::diff(c.y, a.y));
+	bax.mul(&cax).add(&bay.mul(&cay)).estimate()
+}
+
+// Squared distance from `center` to `p`, less `sq_radius`: positive if `p`
+// lies outside the circle, negative if inside, and zero if on it
+pub fn circle_excess(center: Point, sq_radius: f64, p: Point) -> f64 {
+	let (dx, dy) = (p.x-center.x, p.y-center.y);
+	let sq_dist = dx*dx + dy*dy;
+	let excess = sq_dist - sq_radius;
+	if excess.abs() > DOT_ERROR_BOUND * (sq_dist + sq_radius.abs()) || excess.is_nan() {
+		return excess;
+	}
+
+	let (dx, dy) = (Expansion::diff(p.x, center.x), Expansion::diff(p.y, center.y));
+	dx.mul(&dx).add(&dy.mul(&dy)).sub(&Expansion::of(sq_radius)).estimate()
+}
+
+
+// As `circle_excess`, for a sphere
+pub fn sphere_excess(center: Point3, sq_radius: f64, p: Point3) -> f64 {
+	let (dx, dy, dz) = (p.x-center.x, p.y-center.y, p.z-center.z);
+	let sq_dist = dx*dx + dy*dy + dz*dz;
+	let excess = sq_dist - sq_radius;
+	if excess.abs() > DOT_ERROR_BOUND * (sq_dist + sq_radius.abs()) || excess.is_nan() {
+		return excess;
+	}
+
+	let (dx, dy) = (Expansion::diff(p.x, center.x), Expansion::diff(p.y, center.y));
+	let dz = Expansion::diff(p.z, center.z);
+	dx.mul(&dx).add(&dy.mul(&dy)).add(&dz.mul(&dz))
+		.sub(&Expansion::of(sq_radius))
+		.estimate()
+}
+
+
+// As `circle_excess`, for a ball in any number of dimensions
+pub fn ball_excess(center: &[f64], sq_radius: f64, p: &[f64]) -> f64 {
+	let sq_dist: f64 = p.iter().zip(center.iter())
+		.map(|(x, c)| (x-c) * (x-c))
+		.sum();
+	let excess = sq_dist - sq_radius;
+	// Naive summation adds a rounding error per term
+	let error_bound = (p.len() as f64 + 3.) * f64::EPSILON;
+	if excess.abs() > error_bound * (sq_dist + sq_radius.abs()) || excess.is_nan() {
+		return excess;
+	}
+
+	p.iter().zip(center.iter())
+		.map(|(x, c)| Expansion::diff(*x, *c))
+		.fold(Expansion::of(-sq_radius), |sum, d| sum.add(&d.mul(&d)))
+		.estimate()
+}
+
+
+//-----------------------------------------------------------------------------
+// Integer Predicates
+//-----------------------------------------------------------------------------
+
+// As above, for integer coordinates, which an f64 cannot hold exactly; always
+// evaluated in exact arithmetic
+
+pub fn orient2d_i64(a: Point<i64>, b: Point<i64>, c: Point<i64>) -> f64 {
+	let (acx, bcx) = (exact(a.x).sub(&exact(c.x)), exact(b.x).sub(&exact(c.x)));
+	let (acy, bcy) = (exact(a.y).sub(&exact(c.y)), exact(b.y).sub(&exact(c.y)));
+	acx.mul(&bcy).sub(&acy.mul(&bcx)).estimate()
+}
+
+pub fn incircle_i64(a: Point<i64>, b: Point<i64>, c: Point<i64>, d: Point<i64>) -> f64 {
+	let (adx, ady) = (exact(a.x).sub(&exact(d.x)), exact(a.y).sub(&exact(d.y)));
+	let (bdx, bdy) = (exact(b.x).sub(&exact(d.x)), exact(b.y).sub(&exact(d.y)));
+	let (cdx, cdy) = (exact(c.x).sub(&exact(d.x)), exact(c.y).sub(&exact(d.y)));
+	let lift = |dx: &Expansion, dy: &Expansion| dx.mul(dx).add(&dy.mul(dy));
+	let cross = |x1: &Expansion, y1: &Expansion, x2: &Expansion, y2: &Expansion| {
+		x1.mul(y2).sub(&y1.mul(x2))
+	};
+
+	lift(&adx, &ady).mul(&cross(&bdx, &bdy, &cdx, &cdy))
+		.add(&lift(&bdx, &bdy).mul(&cross(&cdx, &cdy, &adx, &ady)))
+		.add(&lift(&cdx, &cdy).mul(&cross(&adx, &ady, &bdx, &bdy)))
+		.estimate()
+}
+
+pub fn dot2d_i64(a: Point<i64>, b: Point<i64>, c: Point<i64>) -> f64 {
+	let (bax, bay) = (exact(b.x).sub(&exact(a.x)), exact(b.y).sub(&exact(a.y)));
+	let (cax, cay) = (exact(c.x).sub(&exact(a.x)), exact(c.y).sub(&exact(a.y)));
+	bax.mul(&cax).add(&bay.mul(&cay)).estimate()
+}
+
+pub fn circle_excess_i64(center: Point<i64>, sq_radius: i64, p: Point<i64>) -> f64 {
+	let (dx, dy) = (exact(p.x).sub(&exact(center.x)), exact(p.y).sub(&exact(center.y)));
+	dx.mul(&dx).add(&dy.mul(&dy)).sub(&exact(sq_radius)).estimate()
+}
+
+// Splits an integer into two 32-bit halves, each of which an f64 holds exactly
+fn exact(a: i64) -> Expansion {
+	let high = (a >> 32) as f64 * (1_u64 << 32) as f64;
+	let low = (a & 0xffff_ffff) as f64;
+	Expansion::of(low).grow(high)
+}
+
+
+//-----------------------------------------------------------------------------
+// Expansion Arithmetic
+//-----------------------------------------------------------------------------
+
+// Exact sum and its rounding error
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+	let sum = a + b;
+	let b_virtual = sum - a;
+	let a_virtual = sum - b_virtual;
+	(sum, (a - a_virtual) + (b - b_virtual))
+}
+
+// Exact product and its rounding error
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+	let product = a * b;
+	(product, a.mul_add(b, -product))
+}
+
+// Exact value, as the sum of non-overlapping, non-zero components in order of
+// increasing magnitude
+#[derive(Debug, Clone, PartialEq)]
+struct Expansion(Vec<f64>);
+
+impl Expansion {
+	fn of(a: f64) -> Self {
+		Self(vec![a]).grow(0.)
+	}
+
+	fn diff(a: f64, b: f64) -> Self {
+		let (sum, error) = two_sum(a, -b);
+		Self::of(error).grow(sum)
+	}
+
+	// Adds a single component, eliminating zeros along the way
+	fn grow(&self, b: f64) -> Self {
+		let mut components = Vec::with_capacity(self.0.len() + 1);
+		let mut sum = b;
+		for e in self.0.iter() {
+			let (new_sum, error) = two_sum(sum, *e);
+			if error != 0. {
+				components.push(error);
+			}
+			sum = new_sum;
+		}
+		if sum != 0. {
+			components.push(sum);
+		}
+		Self(components)
+	}
+
+	fn add(&self, other: &Self) -> Self {
+		other.0.iter().fold(self.clone(), |sum, b| sum.grow(*b))
+	}
+
+	fn sub(&self, other: &Self) -> Self {
+		other.0.iter().fold(self.clone(), |sum, b| sum.grow(-*b))
+	}
+
+	fn mul(&self, other: &Self) -> Self {
+		let mut product = Self(Vec::new());
+		for a in self.0.iter() {
+			for b in other.0.iter() {
+				let (p, error) = two_product(*a, *b);
+				product = product.grow(error).grow(p);
+			}
+		}
+		product
+	}
+
+	// Largest component, which carries the sign of the whole expansion
+	fn estimate(&self) -> f64 {
+		self.0.last().cloned().unwrap_or(0.)
+	}
+}
+
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+	use super::{orient2d, incircle, dot2d, circle_excess, sphere_excess, ball_excess, exact, Expansion, Point, Point3};
+	use std::cmp::Ordering::*;
+
+	#[test]
+	fn expansion() {
+		let e = Expansion::of(1e300).grow(1.).grow(1e-300);
+		assert_eq!(e, Expansion(vec![1e-300, 1., 1e300]));
+		assert_eq!(e.sub(&e), Expansion(vec![]));
+		assert_eq!(Expansion::diff(1., 1e-20).estimate(), 1.);
+		assert_eq!(Expansion::diff(1., 1e-20).sub(&Expansion::of(1.)).estimate(), -1e-20);
+
+		assert_eq!(exact(i64::MAX).add(&exact(1)), Expansion(vec![9223372036854775808.]));
+		assert_eq!(exact(i64::MIN).sub(&exact(i64::MIN)), Expansion(vec![]));
+		assert_eq!(exact(-1).estimate(), -1.);
+	}
+
+	#[test]
+	fn orient2d_() {
+		let a = Point{x: 0., y: 0.};
+		let b = Point{x: 1., y: 0.};
+		assert!(orient2d(a, b, Point{x: 0., y: 1.}) > 0.);
+		assert!(orient2d(a, b, Point{x: 0., y: -1.}) < 0.);
+		assert_eq!(orient2d(a, b, Point{x: 2., y: 0.}), 0.);
+		assert!(orient2d(a, b, Point{x: f64::NAN, y: 0.}).is_nan());
+	}
+
+	#[test]
+	fn orient2d_near_collinear() {
+		// Points along y = x, nudged by single ulps; plain floating point
+		// gets many of these signs wrong
+		let a = Point{x: 0.5, y: 0.5};
+		let c = Point{x: 24., y: 24.};
+		for i in 0..64 {
+			let x = 0.5 + (i as f64) * f64::EPSILON;
+			for (ulps, expected) in [(-1_i64, Less), (0, Equal), (1, Greater)].iter() {
+				let y = f64::from_bits((x.to_bits() as i64 + ulps) as u64);
+				// Points above the line lie to the left of `a` to `c`
+				assert_eq!(orient2d(a, c, Point{x, y}).partial_cmp(&0.), Some(*expected));
+			}
+		}
+	}
+
+	#[test]
+	fn incircle_() {
+		let a = Point{x: 5., y: 0.};
+		let b = Point{x: 0., y: 5.};
+		let c = Point{x: -5., y: 0.};
+		assert!(incircle(a, b, c, Point{x: 0., y: 0.}) > 0.);
+		assert!(incircle(a, b, c, Point{x: 6., y: 0.}) < 0.);
+		assert_eq!(incircle(a, b, c, Point{x: 3., y: -4.}), 0.);
+		assert!(incircle(c, b, a, Point{x: 0., y: 0.}) < 0.);
+
+		// Off the circle by an ulp, which plain floating point cannot resolve
+		let outside = Point{x: 3., y: f64::from_bits((-4_f64).to_bits() + 1)};
+		assert!(incircle(a, b, c, outside) < 0.);
+		let inside = Point{x: 3., y: f64::from_bits((-4_f64).to_bits() - 1)};
+		assert!(incircle(a, b, c, inside) > 0.);
+	}
+
+	#[test]
+	fn dot2d_() {
+		let a = Point{x: 0., y: 0.};
+		assert!(dot2d(a, Point{x: 1., y: 0.}, Point{x: 1., y: 1.}) > 0.);
+		assert!(dot2d(a, Point{x: 1., y: 0.}, Point{x: -1., y: 1.}) < 0.);
+		assert_eq!(dot2d(a, Point{x: 1., y: 0.}, Point{x: 0., y: 1.}), 0.);
+
+		let a = Point{x: 0.1, y: 0.2};
+		assert!(dot2d(a, Point{x: 0.1 + 1e-17, y: 0.3}, Point{x: 0.3, y: 0.2}) > 0.);
+	}
+
+	#[test]
+	fn circle_excess_() {
+		let center = Point{x: 0.5, y: 0.25};
+		assert_eq!(circle_excess(center, 0.25, Point{x: 1., y: 0.25}), 0.);
+		assert!(circle_excess(center, 0.25, Point{x: 0.5, y: 0.5}) < 0.);
+		assert!(circle_excess(center, 0.25, Point{x: 0.5, y: 1.}) > 0.);
+
+		// 0.1 + 0.2 rounds up, so the point is just outside
+		let center = Point{x: 0.1, y: 0.1};
+		let p = Point{x: 0.1 + 0.2, y: 0.1};
+		assert!(circle_excess(center, 0.04, p) > 0.);
+	}
+
+	#[test]
+	fn sphere_excess_() {
+		let center = Point3{x: 0.5, y: 0.25, z: -1.};
+		assert_eq!(sphere_excess(center, 0.25, Point3{x: 0.5, y: 0.25, z: -0.5}), 0.);
+		assert!(sphere_excess(center, 0.25, Point3{x: 0.5, y: 0.5, z: -1.}) < 0.);
+		assert!(sphere_excess(center, 0.25, Point3{x: 0.5, y: 0.5, z: 0.}) > 0.);
+
+		// 0.1 + 0.2 rounds up, so the point is just outside
+		let center = Point3{x: 0.1, y: 0.1, z: 0.1};
+		assert!(sphere_excess(center, 0.04, Point3{x: 0.1, y: 0.1, z: 0.1 + 0.2}) > 0.);
+	}
+
+	#[test]
+	fn ball_excess_() {
+		let center = [0.5, 0.25, -1., 0.];
+		assert_eq!(ball_excess(&center, 0.25, &[0.5, 0.25, -1., 0.5]), 0.);
+		assert!(ball_excess(&center, 0.25, &[0.5, 0.5, -1., 0.]) < 0.);
+		assert!(ball_excess(&center, 0.25, &[0.5, 0.5, 0., 0.]) > 0.);
+		assert!(ball_excess(&[0.1; 5], 0.04, &[0.1, 0.1, 0.1, 0.1, 0.1 + 0.2]) > 0.);
+		assert_eq!(ball_excess(&[], 0., &[]), 0.);
+	}
+}
@@ -0,0 +1,263 @@
+use crate::points::Point;
+use crate::predicates;
+
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Sub, Mul, Neg};
+
+
+// Coordinate type of points, vectors, circles and point clouds.
+//
+// Arithmetic on scalars is used wherever it is exact enough (hull bookkeeping,
+// squared distances), but circle centers are computed in `f64` and rounded to
+// the nearest scalar. Geometric predicates must have exact signs; the default
+// implementations evaluate them exactly on `to_f64` values, and so are exact
+// whenever that conversion is.
+pub trait Scalar:
+	Copy + Default + PartialOrd + Debug + Display
+	+ Add<Output=Self> + Sub<Output=Self> + Mul<Output=Self> + Neg<Output=Self>
+	+ 'static
+{
+	fn to_f64(self) -> f64;
+
+	// Nearest scalar to the given value
+	fn from_f64(x: f64) -> Self;
+
+	fn is_finite(self) -> bool;
+
+	// Adjacent scalars above and below a non-negative value
+	fn next_up(self) -> Self;
+	fn next_down(self) -> Self;
+
+	// Squared distance between two points, rounded to a nearby scalar; `None`
+	// if it overflows
+	fn sq_distance(a: Point<Self>, b: Point<Self>) -> Option<Self> {
+		let sq_distance = Self::from_f64((b.to_f64() - a.to_f64()).sq_mag());
+		Some(sq_distance).filter(|d| d.is_finite())
+	}
+
+	// Whether `a`, `b` and `c` are in counter-clockwise order (`Greater`),
+	// clockwise (`Less`), or collinear; `None` if any coordinate is NaN
+	fn orient2d(a: Point<Self>, b: Point<Self>, c: Point<Self>) -> Option<Ordering> {
+		predicates::orient2d(a.to_f64(), b.to_f64(), c.to_f64()).partial_cmp(&0.)
+	}
+
+	// Whether `d` lies inside (`Greater`), outside (`Less`) or on the circle
+	// through `a`, `b` and `c`, if those are in counter-clockwise order;
+	// reversed if they are clockwise
+	fn incircle(a: Point<Self>, b: Point<Self>, c: Point<Self>, d: Point<Self>) -> Option<Ordering> {
+		predicates::incircle(a.to_f64(), b.to_f64(), c.to_f64(), d.to_f64()).partial_cmp(&0.)
+	}
+
+	// Sign of the dot product of `b - a` and `c - a`
+	fn dot2d(a: Point<Self>, b: Point<Self>, c: Point<Self>) -> Option<Ordering> {
+		predicates::dot2d(a.to_f64(), b.to_f64(), c.to_f64()).partial_cmp(&0.)
+	}
+
+	// Whether `p` lies outside (`Greater`), inside (`Less`) or on the circle
+	// about `center` with the given squared radius
+	fn circle_excess(center: Point<Self>, sq_radius: Self, p: Point<Self>) -> Option<Ordering> {
+		predicates::circle_excess(center.to_f64(), sq_radius.to_f64(), p.to_f64()).partial_cmp(&0.)
+	}
+}
+
+
+macro_rules! impl_float_scalar {
+	($float:ty) => {
+		impl Scalar for $float {
+			fn to_f64(self) -> f64 {
+				self as f64
+			}
+
+			fn from_f64(x: f64) -> Self {
+				x as $float
+			}
+
+			fn is_finite(self) -> bool {
+				<$float>::is_finite(self)
+			}
+
+			fn next_up(self) -> Self {
+				if self.is_finite() {<$float>::from_bits(self.to_bits() + 1)} else {self}
+			}
+
+			fn next_down(self) -> Self {
+				if self > 0. {<$float>::from_bits(self.to_bits() - 1)} else {self}
+			}
+		}
+	};
+}
+
+impl_float_scalar!(f64);
+impl_float_scalar!(f32);
+
+// Every i32 converts exactly to f64, so the default predicates are exact; the
+// `exact` feature evaluates orientations and the like as cross and dot
+// products in i128 instead, which cannot overflow over the whole i32 range.
+// Squared distances are found in i128 too, and are `None` where the result
+// does not fit an i32, so no overflowed squared radius reaches the predicates.
+impl Scalar for i32 {
+	fn to_f64(self) -> f64 {
+		self as f64
+	}
+
+	fn from_f64(x: f64) -> Self {
+		x.round() as i32
+	}
+
+	fn is_finite(self) -> bool {
+		true
+	}
+
+	fn next_up(self) -> Self {
+		self.saturating_add(1)
+	}
+
+	fn next_down(self) -> Self {
+		self.saturating_sub(1)
+	}
+
+	fn sq_distance(a: Point<Self>, b: Point<Self>) -> Option<Self> {
+		let d = wide_diff(b, a);
+		i32::try_from(d.0*d.0 + d.1*d.1).ok()
+	}
+
+	#[cfg(feature = "exact")]
+	fn orient2d(a: Point<Self>, b: Point<Self>, c: Point<Self>) -> Option<Ordering> {
+		let (ac, bc) = (wide_diff(a, c), wide_diff(b, c));
+		Some((ac.0*bc.1).cmp(&(ac.1*bc.0)))
+	}
+
+	#[cfg(feature = "exact")]
+	fn dot2d(a: Point<Self>, b: Point<Self>, c: Point<Self>) -> Option<Ordering> {
+		let (ba, ca) = (wide_diff(b, a), wide_diff(c, a));
+		Some((ba.0*ca.0 + ba.1*ca.1).cmp(&0))
+	}
+
+	#[cfg(feature = "exact")]
+	fn circle_excess(center: Point<Self>, sq_radius: Self, p: Point<Self>) -> Option<Ordering> {
+		let d = wide_diff(p, center);
+		Some((d.0*d.0 + d.1*d.1).cmp(&(sq_radius as i128)))
+	}
+}
+
+fn wide_diff(p1: Point<i32>, p2: Point<i32>) -> (i128, i128) {
+	(p1.x as i128 - p2.x as i128, p1.y as i128 - p2.y as i128)
+}
+
+// `to_f64` is not exact, so the predicates are evaluated on the integers
+// themselves; squared distances are found in i128, and only overflow where
+// the result does not fit an i64
+impl Scalar for i64 {
+	fn to_f64(self) -> f64 {
+		self as f64
+	}
+
+	fn from_f64(x: f64) -> Self {
+		x.round() as i64
+	}
+
+	fn is_finite(self) -> bool {
+		true
+	}
+
+	fn next_up(self) -> Self {
+		self.saturating_add(1)
+	}
+
+	fn next_down(self) -> Self {
+		self.saturating_sub(1)
+	}
+
+	fn sq_distance(a: Point<Self>, b: Point<Self>) -> Option<Self> {
+		let (dx, dy) = (b.x as i128 - a.x as i128, b.y as i128 - a.y as i128);
+		let sq_distance = dx.checked_mul(dx)?.checked_add(dy.checked_mul(dy)?)?;
+		i64::try_from(sq_distance).ok()
+	}
+
+	fn orient2d(a: Point<Self>, b: Point<Self>, c: Point<Self>) -> Option<Ordering> {
+		predicates::orient2d_i64(a, b, c).partial_cmp(&0.)
+	}
+
+	fn incircle(a: Point<Self>, b: Point<Self>, c: Point<Self>, d: Point<Self>) -> Option<Ordering> {
+		predicates::incircle_i64(a, b, c, d).partial_cmp(&0.)
+	}
+
+	fn dot2d(a: Point<Self>, b: Point<Self>, c: Point<Self>) -> Option<Ordering> {
+		predicates::dot2d_i64(a, b, c).partial_cmp(&0.)
+	}
+
+	fn circle_excess(center: Point<Self>, sq_radius: Self, p: Point<Self>) -> Option<Ordering> {
+		predicates::circle_excess_i64(center, sq_radius, p).partial_cmp(&0.)
+	}
+}
+
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+	use super::{Scalar, Point};
+	use std::cmp::Ordering::*;
+
+	#[test]
+	fn conversions() {
+		assert_eq!(f32::from_f64(0.1), 0.1_f32);
+		assert_eq!(i32::from_f64(2.5), 3);
+		assert_eq!(i64::from_f64(-2.5), -3);
+		assert_eq!(i64::from_f64(f64::NAN), 0);
+		assert!(!f32::NAN.is_finite());
+		assert!(Scalar::is_finite(i64::MAX));
+	}
+
+	#[test]
+	fn adjacent() {
+		// Qualified, as floats have inherent methods of the same names
+		assert_eq!(Scalar::next_up(1_f64), 1. + f64::EPSILON);
+		assert_eq!(Scalar::next_down(1_f32), 1. - f32::EPSILON / 2.);
+		assert_eq!(Scalar::next_down(0_f64), 0.);
+		assert_eq!(Scalar::next_up(f64::INFINITY), f64::INFINITY);
+		assert_eq!(7_i64.next_up(), 8);
+		assert_eq!(7_i32.next_down(), 6);
+	}
+
+	#[test]
+	fn sq_distance() {
+		let (a, b) = (Point{x: -20000, y: 0}, Point{x: 20000, y: 20000});
+		assert_eq!(i32::sq_distance(a, b), Some(2_000_000_000));
+		assert_eq!(i32::sq_distance(Point{x: -60000, y: 0}, Point{x: 60000, y: 0}), None);
+		assert_eq!(i64::sq_distance(Point{x: 0, y: 0}, Point{x: 1_000_000_000, y: 2_000_000_000}), Some(5 * 10_i64.pow(18)));
+		assert_eq!(i64::sq_distance(Point{x: i64::MIN, y: 0}, Point{x: 0, y: 0}), None);
+		assert_eq!(f64::sq_distance(Point{x: 1., y: 2.}, Point{x: 4., y: 6.}), Some(25.));
+		assert_eq!(f32::sq_distance(Point{x: 0., y: 0.}, Point{x: 1e30, y: 0.}), None);
+	}
+
+	#[test]
+	fn predicates() {
+		// Beyond the precision of f64
+		let big = 1_i64 << 60;
+		let a = Point{x: -big, y: -big};
+		let b = Point{x: big, y: big};
+		assert_eq!(i64::orient2d(a, b, Point{x: big - 2, y: big - 1}), Some(Greater));
+		assert_eq!(i64::orient2d(a, b, Point{x: big - 1, y: big - 1}), Some(Equal));
+		assert_eq!(i64::orient2d(a, b, Point{x: big - 1, y: big - 2}), Some(Less));
+
+		let (a, b, c) = (Point{x: 5, y: 0}, Point{x: 0, y: 5}, Point{x: -5, y: 0});
+		assert_eq!(i64::incircle(a, b, c, Point{x: 3, y: -4}), Some(Equal));
+		assert_eq!(i64::incircle(a, b, c, Point{x: 3, y: -5}), Some(Less));
+		let (lo, hi) = (Point{x: i32::MIN, y: i32::MIN}, Point{x: i32::MAX, y: i32::MAX});
+		assert_eq!(i32::orient2d(lo, hi, Point{x: i32::MAX - 1, y: i32::MAX}), Some(Greater));
+		assert_eq!(i32::orient2d(lo, hi, Point{x: 1, y: 0}), Some(Less));
+		assert_eq!(i32::dot2d(lo, hi, Point{x: i32::MIN, y: i32::MAX}), Some(Greater));
+		assert_eq!(i32::circle_excess(Point{x: 0, y: 0}, i32::MAX, hi), Some(Greater));
+
+		let (a32, b32, c32) = (Point{x: 5, y: 0}, Point{x: 0, y: 5}, Point{x: -5, y: 0});
+		assert_eq!(i32::incircle(a32, b32, c32, Point{x: 0, y: 0}), Some(Greater));
+
+		assert_eq!(i64::dot2d(Point{x: big, y: 0}, Point{x: 0, y: 0}, Point{x: big, y: 1}), Some(Equal));
+		assert_eq!(i64::circle_excess(Point{x: 0, y: 0}, 25, Point{x: 3, y: 4}), Some(Equal));
+		assert_eq!(f32::circle_excess(Point{x: 0., y: 0.}, 1., Point{x: 1., y: 0.1}), Some(Greater));
+		assert_eq!(f64::orient2d(a.to_f64(), b.to_f64(), Point{x: f64::NAN, y: 0.}), None);
+	}
+}
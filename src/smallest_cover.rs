@@ -1,158 +1,1001 @@
-use crate::points::{Point, Vector};
+use crate::points::{Point, Point3, cmp_lexicographic};
+use crate::scalar::Scalar;
+use crate::hull::DynamicHull;
+use crate::fifo::FifoHull;
+use crate::circle::{self, Candidates, ClosedCircle, Covering, EXACT_OUTLIERS};
 use crate::polygon::ConvexPolygon;
-use crate::circle::ClosedCircle;
+use crate::sphere::ClosedSphere;
+use crate::error::Error;
 
+use std::cmp::Ordering;
+use std::convert::TryFrom;
 use std::iter::Iterator;
-use std::collections::HashSet;
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, BTreeSet};
 
 
-pub struct MovingPointCloud{
-	cover: ConvexPolygon,
-	interior: HashSet<(u64, u64)>,
-	point_log: VecDeque<Point>,
+// Stable identifier for a point pushed to a `MovingPointCloud`; handles are
+// never reused, and order by age
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointHandle(pub(crate) u64);
+
+
+// Multiset of logged points, with the handles of the copies at each position
+#[derive(Debug, Clone)]
+struct PointBag<T> {
+	handles: BTreeMap<Position<T>, BTreeSet<PointHandle>>,
+	len: usize,
+}
+
+// Point ordered lexicographically, as a map key
+#[derive(Debug, Clone, Copy)]
+struct Position<T>(Point<T>);
+
+impl<T: Scalar> PointBag<T> {
+	fn new() -> Self {
+		Self{handles: BTreeMap::new(), len: 0}
+	}
+
+	fn len(&self) -> usize {
+		self.len
+	}
+
+	fn insert(&mut self, handle: PointHandle, p: Point<T>) {
+		self.handles.entry(Position(p)).or_default().insert(handle);
+		self.len += 1;
+	}
+
+	fn remove(&mut self, handle: PointHandle, p: Point<T>) -> bool {
+		let handles = match self.handles.get_mut(&Position(p)) {
+			Some(handles) => handles,
+			None => return false,
+		};
+		if !handles.remove(&handle) {
+			return false;
+		}
+		if handles.is_empty() {
+			self.handles.remove(&Position(p));
+		}
+		self.len -= 1;
+		true
+	}
+
+	// Number of copies of the point
+	fn count(&self, p: Point<T>) -> usize {
+		self.handles.get(&Position(p)).map_or(0, |handles| handles.len())
+	}
+
+	// Distinct positions in lexicographic order, each with its number of
+	// copies
+	fn counts(&self) -> Candidates<T> {
+		self.handles.iter().map(|(position, handles)| (position.0, handles.len())).collect()
+	}
+
+	// Handle of the newest copy of the point
+	fn newest(&self, p: Point<T>) -> Option<PointHandle> {
+		self.handles.get(&Position(p))?.iter().next_back().copied()
+	}
+
+	fn contains(&self, handle: PointHandle, p: Point<T>) -> bool {
+		self.handles.get(&Position(p)).is_some_and(|handles| handles.contains(&handle))
+	}
+}
+
+impl<T: Scalar> PartialEq for Position<T> {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl<T: Scalar> Eq for Position<T> {}
+
+impl<T: Scalar> PartialOrd for Position<T> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<T: Scalar> Ord for Position<T> {
+	fn cmp(&self, other: &Self) -> Ordering {
+		cmp_lexicographic(&self.0, &other.0)
+	}
+}
+
+
+// Points logged by a cloud, by handle, with the handles given out so far and
+// the treatment of new points with non-finite coordinates
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct PointLog<P> {
+	points: BTreeMap<PointHandle, P>,
+	next_handle: PointHandle,
+	non_finite_policy: NonFinitePolicy,
+	skipped: usize,
+}
+
+// Point type of a `PointLog`
+trait LoggedPoint: Copy {
+	// The error rejecting the point, if it has a non-finite coordinate
+	fn check_finite(&self) -> Result<(), Error>;
+
+	// Infinite coordinates clamped to within `limit` of zero; `None` if any
+	// coordinate is NaN
+	fn clamped(&self, limit: f64) -> Option<Self>;
+}
+
+impl<T: Scalar> LoggedPoint for Point<T> {
+	fn check_finite(&self) -> Result<(), Error> {
+		if self.is_finite() {Ok(())} else {Err(Error::NonFinitePoint(self.to_f64()))}
+	}
+
+	fn clamped(&self, limit: f64) -> Option<Self> {
+		if self.to_f64().x.is_nan() || self.to_f64().y.is_nan() {
+			return None;
+		}
+		let clamp = |c: T| match c.to_f64() {
+			c if c.is_infinite() => T::from_f64(limit.abs().copysign(c)),
+			_ => c,
+		};
+		Some(Point{x: clamp(self.x), y: clamp(self.y)})
+	}
 }
 
+impl LoggedPoint for Point3 {
+	fn check_finite(&self) -> Result<(), Error> {
+		if self.is_finite() {Ok(())} else {Err(Error::NonFinitePoint3(*self))}
+	}
+
+	fn clamped(&self, limit: f64) -> Option<Self> {
+		if self.x.is_nan() || self.y.is_nan() || self.z.is_nan() {
+			return None;
+		}
+		let clamp = |c: f64| if c.is_infinite() {limit.abs().copysign(c)} else {c};
+		Some(Point3{x: clamp(self.x), y: clamp(self.y), z: clamp(self.z)})
+	}
+}
+
+impl<P: LoggedPoint> PointLog<P> {
+	fn new() -> Self {
+		Self{
+			points: BTreeMap::new(),
+			next_handle: PointHandle(0),
+			non_finite_policy: NonFinitePolicy::default(),
+			skipped: 0,
+		}
+	}
+
+	fn set_non_finite_policy(&mut self, policy: NonFinitePolicy) {
+		if let NonFinitePolicy::Clamp(limit) = policy {
+			assert!(limit.is_finite(), "clamp limit must be finite");
+		}
+		self.non_finite_policy = policy;
+	}
+
+	// Gives out the handle for a new point, and applies the non-finite policy
+	// to it: `None` for a point to skip, which is counted. Fails if the
+	// policy rejects the point, giving out no handle.
+	fn admit(&mut self, p: P) -> Result<(PointHandle, Option<P>), Error> {
+		let p = match p.check_finite() {
+			Ok(()) => Some(p),
+			Err(e) => match self.non_finite_policy {
+				NonFinitePolicy::Reject => return Err(e),
+				NonFinitePolicy::Skip => None,
+				NonFinitePolicy::Clamp(limit) => p.clamped(limit),
+			},
+		};
+
+		let handle = self.next_handle;
+		self.next_handle = PointHandle(handle.0 + 1);
+		if p.is_none() {
+			self.skipped += 1;
+		}
+		Ok((handle, p))
+	}
+
+	fn first(&self) -> Result<(PointHandle, P), Error> {
+		self.points.iter().next()
+			.map(|(handle, p)| (*handle, *p))
+			.ok_or(Error::EmptyCloud)
+	}
+
+	// Checks what a log built by `admit` upholds: that the policy is valid,
+	// the points are finite, and every handle was given out, once
+	fn check(&self) -> Result<(), &'static str> {
+		if let NonFinitePolicy::Clamp(limit) = self.non_finite_policy {
+			if !limit.is_finite() {
+				return Err("clamp limit is not finite");
+			}
+		}
+		if self.points.keys().next_back().is_some_and(|handle| *handle >= self.next_handle) {
+			return Err("point handle is not older than the next handle");
+		}
+		let handles_taken = self.points.len() as u64 + self.skipped as u64;
+		if handles_taken > self.next_handle.0 {
+			return Err("more points logged and skipped than handles given out");
+		}
+		if !self.points.values().all(|p| p.check_finite().is_ok()) {
+			return Err("logged point has a non-finite coordinate");
+		}
+		Ok(())
+	}
+}
+
+
+// Data structure maintaining the convex hull of a `MovingPointCloud`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Backend {
+	// Balanced hull tree; O(log³ n) expected per push or removal, in any
+	// order, as each of the O(log n) bridges rebuilt takes O(log² n)
+	#[default]
+	Dynamic,
+	// Two-stack queue of incremental hulls; O(h) amortized per push or pop
+	// for h hull vertices, and removing a point redoes the insertions made
+	// after it into its stack's hull, O(k h) for k such points
+	Fifo,
+}
+
+// Treatment of points with NaN or infinite coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NonFinitePolicy {
+	// Fail the push
+	#[default]
+	Reject,
+	// Drop the point, and count it
+	Skip,
+	// Clamp infinite coordinates to within the given (finite) limit of zero,
+	// rounded to the scalar type; points with NaN coordinates have no
+	// sensible clamp, and are skipped
+	Clamp(f64),
+}
+
+#[derive(Debug)]
+enum Cover<T> {
+	Dynamic(DynamicHull<T>),
+	Fifo(FifoHull<T>),
+}
+
+
+// With the `serde` feature, the cloud is (de)serialized as its snapshot, so
+// that a restored cloud carries on exactly where the original left off; a
+// snapshot that was not taken of any cloud fails to deserialize (see
+// `from_snapshot`)
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(
+	try_from = "CloudSnapshot<T>",
+	bound(deserialize = "T: Scalar + serde::Deserialize<'de>"),
+))]
+pub struct MovingPointCloud<T = f64>{
+	cover: Cover<T>,
+	point_log: PointLog<Point<T>>,
+	// Logged points by position; the hull and circle only change when the
+	// first copy of a point arrives, or the last one leaves
+	positions: PointBag<T>,
+	// Smallest circle covering the points, with the support points it is
+	// built from; always the same as `ClosedCircle::covering` of the logged
+	// points, but only recomputed when a point that is not strictly within
+	// it arrives, or one that shapes it leaves. The circle is `None` if its
+	// squared radius overflows the scalar type.
+	circle: Option<Covering<T>>,
+	circle_changed: bool,
+	// Called on every change of the circle or hull; not part of the cloud's
+	// state
+	observer: Option<Observer<T>>,
+}
+
+type Observer<T> = Box<dyn FnMut(&CircleChange<T>) + Send + Sync>;
+
+// Change of the cover circle or hull of a `MovingPointCloud` by one push,
+// pop or removal, as passed to the observer
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CircleChange<T = f64> {
+	// `None` for an empty cloud, or one whose circle overflows the scalar
+	// type
+	pub old_circle: Option<ClosedCircle<T>>,
+	pub new_circle: Option<ClosedCircle<T>>,
+	// Points on each circle that determine it, in lexicographic order
+	pub old_support: Vec<Point<T>>,
+	pub new_support: Vec<Point<T>>,
+	// Vertices of the convex hull that the update added and removed, in
+	// lexicographic order
+	pub hull_added: Vec<Point<T>>,
+	pub hull_removed: Vec<Point<T>>,
+}
+
+// Cover circle of a `MovingPointCloud`, with the logged points on its
+// boundary that determine it
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SupportedCircle<T = f64> {
+	pub circle: ClosedCircle<T>,
+	// One point for a cloud of coincident points, otherwise two or three,
+	// in lexicographic order; where several logged points coincide with a
+	// support point, the newest one
+	pub support: Vec<(PointHandle, Point<T>)>,
+}
+
+// Smallest circle covering all but a few points of a `MovingPointCloud`, with
+// the points it leaves out
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OutlierCircle<T = f64> {
+	pub circle: ClosedCircle<T>,
+	// Oldest first
+	pub outliers: Vec<(PointHandle, Point<T>)>,
+	// Whether the circle is known to be the smallest, or else was found
+	// greedily (and may leave out fewer points than it could)
+	pub exact: bool,
+}
+
+// Owned copy of the state of a `MovingPointCloud`, from which
+// `MovingPointCloud::from_snapshot` rebuilds an equal cloud
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CloudSnapshot<T = f64> {
+	pub backend: Backend,
+	pub non_finite_policy: NonFinitePolicy,
+	// Logged points, oldest first
+	pub points: Vec<(PointHandle, Point<T>)>,
+	pub next_handle: PointHandle,
+	pub skipped: usize,
+	// Vertices of the convex hull of the points, counter-clockwise from the
+	// lexicographically smallest; every other point is interior to it (or on
+	// its boundary)
+	pub hull: Vec<Point<T>>,
+}
+
+
 impl MovingPointCloud {
+	// Empty cloud of f64 points; for other scalar types, use `default` or
+	// `with_backend`
 	pub fn new() -> Self {
+		Self::default()
+	}
+}
+
+impl<T: Scalar> MovingPointCloud<T> {
+	pub fn with_backend(backend: Backend) -> Self {
 		Self{
-			cover: ConvexPolygon::new(),
-			interior: HashSet::new(),
-			point_log: VecDeque::new(),
+			cover: match backend {
+				Backend::Dynamic => Cover::Dynamic(DynamicHull::new()),
+				Backend::Fifo => Cover::Fifo(FifoHull::new()),
+			},
+			point_log: PointLog::new(),
+			positions: PointBag::new(),
+			circle: None,
+			circle_changed: false,
+			observer: None,
+		}
+	}
+
+	// The same empty cloud on another backend, keeping its handles, policy
+	// and observer
+	pub(crate) fn on_backend(self, backend: Backend) -> Self {
+		debug_assert!(self.is_empty());
+		Self{point_log: self.point_log, observer: self.observer, ..Self::with_backend(backend)}
+	}
+
+	pub fn with_non_finite_policy(mut self, policy: NonFinitePolicy) -> Self {
+		self.point_log.set_non_finite_policy(policy);
+		self
+	}
+
+	pub fn non_finite_policy(&self) -> NonFinitePolicy {
+		self.point_log.non_finite_policy
+	}
+
+	// Calls `observer` after every push, pop or removal that changes the
+	// cover circle or the hull, replacing any earlier observer. Finding the
+	// hull vertices that changed takes O(h log h) time for h vertices, on
+	// top of any update that adds the first copy of a point or removes the
+	// last.
+	pub fn with_observer<F: FnMut(&CircleChange<T>) + Send + Sync + 'static>(self, observer: F) -> Self {
+		Self{observer: Some(Box::new(observer)), ..self}
+	}
+
+	// Number of points skipped under the non-finite policy
+	pub fn skipped(&self) -> usize {
+		self.point_log.skipped
+	}
+
+	pub fn backend(&self) -> Backend {
+		match self.cover {
+			Cover::Dynamic(_) => Backend::Dynamic,
+			Cover::Fifo(_) => Backend::Fifo,
 		}
 	}
 
 	pub fn len(&self) -> usize {
-		assert_eq!(self.point_log.len(), self.cover.degree() + self.interior.len());
-		self.point_log.len()
+		let cover_len = match &self.cover {
+			Cover::Dynamic(hull) => hull.len(),
+			Cover::Fifo(hull) => hull.len(),
+		};
+		assert_eq!(self.point_log.points.len(), cover_len);
+		assert_eq!(self.point_log.points.len(), self.positions.len());
+		self.point_log.points.len()
 	}
 
-	pub fn extend<I: Iterator<Item=Point>>(&mut self, iter: I) {
+	pub fn is_empty(&self) -> bool {
+		self.point_log.points.is_empty()
+	}
+
+	// Number of vertices of the convex hull of the points, without copying
+	// out the point log as `snapshot` does
+	pub fn hull_len(&self) -> usize {
+		ConvexPolygon::hull_of(&self.hull_vertices()).degree()
+	}
+
+	pub fn extend<I: Iterator<Item=Point<T>>>(&mut self, iter: I) {
 		for p in iter {
 			self.push(p);
 		}
 	}
 
-	pub fn push(&mut self, p: Point) {
-		self.point_log.push_back(p);
-		for p in self.cover.insert(p).into_iter() {
-			self.interior.insert(p.to_bits());
+	pub fn get(&self, handle: PointHandle) -> Option<Point<T>> {
+		self.point_log.points.get(&handle).cloned()
+	}
+
+	// Iterates over logged points, from oldest to newest
+	pub fn iter(&self) -> impl Iterator<Item=(PointHandle, Point<T>)> + '_ {
+		self.point_log.points.iter().map(|(h, p)| (*h, *p))
+	}
+
+	// Panics on points with non-finite coordinates, if the policy rejects
+	// them
+	pub fn push(&mut self, p: Point<T>) -> PointHandle {
+		self.try_push(p).unwrap_or_else(|e| panic!("{}", e))
+	}
+
+	// Fails on points with non-finite coordinates, if the policy rejects them,
+	// leaving the cloud unchanged. A skipped point still takes up a handle,
+	// which refers to no point.
+	pub fn try_push(&mut self, p: Point<T>) -> Result<PointHandle, Error> {
+		self.push_sanitized(p).map(|(handle, _logged)| handle)
+	}
+
+	// Also returns whether the point was logged, or else skipped
+	pub(crate) fn push_sanitized(&mut self, p: Point<T>) -> Result<(PointHandle, bool), Error> {
+		let (handle, p) = match self.point_log.admit(p)? {
+			(handle, Some(p)) => (handle, p),
+			(handle, None) => {
+				self.circle_changed = false;
+				return Ok((handle, false));
+			},
+		};
+		// A point strictly within the circle through the support points,
+		// and covered once rounding is absorbed, leaves the support and the
+		// circle as they would be computed afresh
+		let covered = self.circle.as_ref().is_some_and(|(circle, support)| {
+			circle::support_side(support, p) == Some(Ordering::Less)
+				&& circle.is_none_or(|circle| circle.covers(p))
+		});
+		let old_hull = self.observed_hull(self.positions.count(p) == 0);
+		self.log(handle, p);
+		self.update_circle(!covered, old_hull);
+		self.check_invariants();
+		Ok((handle, true))
+	}
+
+	// Adds a point to both the log and the hull; it must be newer than every
+	// logged point
+	fn log(&mut self, handle: PointHandle, p: Point<T>) {
+		self.point_log.points.insert(handle, p);
+		self.positions.insert(handle, p);
+		match &mut self.cover {
+			Cover::Dynamic(hull) => hull.insert(p),
+			Cover::Fifo(hull) => hull.push(handle, p),
+		};
+	}
+
+	// Removes the oldest point
+	pub fn pop(&mut self) -> Option<Point<T>> {
+		match self.try_pop() {
+			Ok(p) => Some(p),
+			Err(Error::EmptyCloud) => None,
+			Err(e) => panic!("{}", e),
+		}
+	}
+
+	pub fn try_pop(&mut self) -> Result<Point<T>, Error> {
+		let (handle, p) = self.point_log.first()?;
+		let last_copy = self.positions.count(p) == 1;
+		let old_hull = self.observed_hull(last_copy);
+		self.point_log.points.pop_first();
+		let unlogged = self.positions.remove(handle, p) && match &mut self.cover {
+			Cover::Dynamic(hull) => hull.remove(p),
+			Cover::Fifo(hull) => hull.pop() == Some(p),
+		};
+		if !unlogged {
+			return Err(Error::InconsistentState("popped point is missing from the hull"));
+		}
+		self.unlogged(p, last_copy, old_hull);
+		Ok(p)
+	}
+
+	// Removes the point with the given handle, regardless of its age; `None`
+	// if the handle refers to no logged point
+	pub fn remove(&mut self, handle: PointHandle) -> Option<Point<T>> {
+		match self.try_remove(handle) {
+			Ok(p) => Some(p),
+			Err(Error::UnknownHandle(_handle)) => None,
+			Err(e) => panic!("{}", e),
 		}
 	}
 
-	pub fn pop(&mut self) {
-		if let Some(p) = self.point_log.pop_front() {
-			if self.interior.remove::<(u64, u64)>(&p.to_bits()) {}
+	// Fails if the handle refers to no logged point: one never given out,
+	// skipped, or already removed
+	pub fn try_remove(&mut self, handle: PointHandle) -> Result<Point<T>, Error> {
+		let p = self.get(handle).ok_or(Error::UnknownHandle(handle))?;
+		let last_copy = self.positions.count(p) == 1;
+		let old_hull = self.observed_hull(last_copy);
+		self.point_log.points.remove(&handle);
+		let unlogged = self.positions.remove(handle, p) && match &mut self.cover {
+			Cover::Dynamic(hull) => hull.remove(p),
+			Cover::Fifo(hull) => hull.remove(handle) == Some(p),
+		};
+		if !unlogged {
+			return Err(Error::InconsistentState("removed point is missing from the hull"));
+		}
+		self.unlogged(p, last_copy, old_hull);
+		Ok(p)
+	}
 
-			else if let Some(vertex) = self.cover.find(p) {
-				let mut lost_area = ConvexPolygon::new();
-				lost_area.insert(vertex.rev_vertex().position());
-				lost_area.insert(vertex.fwd_vertex().position());
-				let vertex_id = vertex.to_id();
-				lost_area.insert(self.cover.remove(vertex_id));
+	// Updates the circle once a point has left the cloud; it only changes if
+	// the last copy of a point that shapes it left
+	fn unlogged(&mut self, p: Point<T>, last_copy: bool, old_hull: Option<Vec<Point<T>>>) {
+		self.update_circle(last_copy && self.shapes_circle(p), old_hull);
+		self.check_invariants();
+	}
 
-				let mut move_to_cover = Vec::<Point>::new();
+	// Whether the circle depends on the point: it is a support point, or the
+	// circle through the support points was widened to cover it
+	fn shapes_circle(&self, p: Point<T>) -> bool {
+		self.circle.as_ref().is_none_or(|(_circle, support)| {
+			support.contains(&p) || !ClosedCircle::from_support(support).covers(p)
+		})
+	}
 
-				for p in self.interior.iter()
-					.map(|b| Point::from_bits(*b))
-					.filter(|p| lost_area.covers(*p))
-				{
-					let move_to_interior = self.cover.insert(p);
-					if move_to_interior.len() == 0 {
-						move_to_cover.push(p);
-					} else {
-						assert_eq!(move_to_interior.len(), 1);
-						assert_eq!(move_to_interior[0], p);
-					}
-				}
-				for p in move_to_cover.into_iter() {
-					self.interior.remove(&p.to_bits());
-				}
-			}
+	// Hull before an update that may change it, if there is an observer to
+	// tell how it changed; only the first copy of a point to arrive, or the
+	// last to leave, can change it
+	fn observed_hull(&self, changes_hull: bool) -> Option<Vec<Point<T>>> {
+		Some(self.hull()).filter(|_hull| changes_hull && self.observer.is_some())
+	}
 
-			else {
-				unreachable!();
-			}
+	// Recomputes the circle if asked to, and tells the observer if the circle
+	// or the hull changed; `old_hull` is the hull before the update, if it
+	// may have changed and there is an observer. The hull vertices yield the
+	// same circle as every logged point would, bit for bit.
+	fn update_circle(&mut self, recompute: bool, old_hull: Option<Vec<Point<T>>>) {
+		let old = if recompute {
+			let circle = ClosedCircle::covering_with_support(&self.hull_vertices());
+			Some(std::mem::replace(&mut self.circle, circle))
+		} else {
+			None
+		};
+		let old = old.as_ref().unwrap_or(&self.circle);
+		let circle = |covering: &Option<Covering<T>>| covering.as_ref().and_then(|(circle, _support)| *circle);
+		let (old_circle, new_circle) = (circle(old), circle(&self.circle));
+		self.circle_changed = new_circle != old_circle;
+
+		// With the same set of positions, the circle is the same too
+		let old_hull = match old_hull {
+			Some(old_hull) => old_hull,
+			None => return,
+		};
+		let new_hull = self.hull();
+		if !self.circle_changed && new_hull == old_hull {
+			return;
+		}
+		let support = |covering: &Option<Covering<T>>| {
+			covering.as_ref().map(|(_circle, support)| support.clone()).unwrap_or_default()
+		};
+		let change = CircleChange{
+			old_circle,
+			new_circle,
+			old_support: support(old),
+			new_support: support(&self.circle),
+			hull_added: difference(&new_hull, &old_hull),
+			hull_removed: difference(&old_hull, &new_hull),
+		};
+		if let Some(observer) = &mut self.observer {
+			observer(&change);
 		}
 	}
 
-	pub fn cover_circle(&self) -> ClosedCircle {
-		match self.cover.degree() {
-			0 => return ClosedCircle {
-				center: Point{x: std::f64::NAN, y: std::f64::NAN},
-				sq_radius: 0.
-			},
-			1 => return ClosedCircle {
-				center: self.cover.some_vertex().position(),
-				sq_radius: 0.
-			},
-			_ => {}
+	// Whether the last push, pop or removal changed the cover circle
+	pub fn circle_changed(&self) -> bool {
+		self.circle_changed
+	}
+
+	// Legacy form, kept for existing callers: for an empty cloud, a circle of
+	// zero radius about a NaN center (the origin, for integer scalars), and
+	// for one whose circle overflows, the same. New code should use
+	// `checked_cover_circle` or `try_cover_circle`, which tell these apart.
+	pub fn cover_circle(&self) -> ClosedCircle<T> {
+		self.try_cover_circle().unwrap_or(ClosedCircle{
+			center: Point::from_f64(Point{x: f64::NAN, y: f64::NAN}),
+			sq_radius: T::default(),
+		})
+	}
+
+	// `None` if the cloud is empty, or its circle overflows the scalar type
+	pub fn checked_cover_circle(&self) -> Option<ClosedCircle<T>> {
+		match self.try_cover_circle() {
+			Ok(circle) => Some(circle),
+			Err(Error::EmptyCloud) | Err(Error::RadiusOverflow) => None,
+			Err(e) => panic!("{}", e),
+		}
+	}
+
+	// Fails on an empty cloud, or one whose circle has a squared radius too
+	// large for the scalar type
+	pub fn try_cover_circle(&self) -> Result<ClosedCircle<T>, Error> {
+		if self.is_empty() {
+			return Err(Error::EmptyCloud);
+		}
+		match &self.circle {
+			Some((Some(circle), _support)) => Ok(*circle),
+			Some((None, _support)) => Err(Error::RadiusOverflow),
+			None => Err(Error::InconsistentState("non-empty cloud has no cover circle")),
+		}
+	}
+
+	// `None` if the cloud is empty, or its circle overflows the scalar type.
+	// Finding the handles of the support points takes O(log n) time.
+	pub fn supported_cover_circle(&self) -> Result<Option<SupportedCircle<T>>, Error> {
+		let circle = match self.try_cover_circle() {
+			Ok(circle) => circle,
+			Err(Error::EmptyCloud) | Err(Error::RadiusOverflow) => return Ok(None),
+			Err(e) => return Err(e),
 		};
+		let support = self.circle.as_ref().map_or(&[][..], |(_circle, support)| support);
+		let support = support.iter()
+			.map(|s| {
+				self.positions.newest(*s)
+					.map(|handle| (handle, *s))
+					.ok_or(Error::InconsistentState("support point is not a logged point"))
+			})
+			.collect::<Result<_, _>>()?;
+		Ok(Some(SupportedCircle{circle, support}))
+	}
 
-		// Calculate square center
-		let center = {
-			let x_bounds = (
-				self.cover.find_best(Vector{x: -1., y: 0.}).position().x,
-				self.cover.find_best(Vector{x: 1., y: 0.}).position().x,
-			);
-			let y_bounds = (
-				self.cover.find_best(Vector{x: 0., y: -1.}).position().y,
-				self.cover.find_best(Vector{x: 0., y: 1.}).position().y,
-			);
-
-			Point{
-				x: (x_bounds.0+x_bounds.1) / 2.,
-				y: (y_bounds.0+y_bounds.1) / 2.,
-			}
+	// Smallest circle covering all but at most `k` logged points, so that a
+	// few spurious points do not blow it up; `None` if the cloud is empty.
+	// Points at the same position are left out together, and at least one is
+	// kept. Exact for `k` up to 4, in O(3^k n) time on top of peeling k
+	// layers of the hull within the one the cloud keeps, in O(k n log n);
+	// beyond that, found greedily. Leaving out no points gives the cover
+	// circle, bit for bit.
+	pub fn cover_circle_with_outliers(&self, k: usize) -> Option<OutlierCircle<T>> {
+		if k == 0 {
+			let circle = self.checked_cover_circle()?;
+			return Some(OutlierCircle{circle, outliers: Vec::new(), exact: true});
+		}
+
+		let (circle, left_out) = ClosedCircle::covering_all_but_counted(&self.hull(), self.positions.counts(), k)?;
+		let is_left_out = |p: &Point<T>| left_out.binary_search_by(|q| cmp_lexicographic(q, p)).is_ok();
+		Some(OutlierCircle{
+			circle,
+			outliers: self.iter().filter(|(_handle, p)| is_left_out(p)).collect(),
+			exact: k <= EXACT_OUTLIERS,
+		})
+	}
+
+	pub fn snapshot(&self) -> CloudSnapshot<T> {
+		CloudSnapshot{
+			backend: self.backend(),
+			non_finite_policy: self.point_log.non_finite_policy,
+			points: self.iter().collect(),
+			next_handle: self.point_log.next_handle,
+			skipped: self.point_log.skipped,
+			hull: self.hull(),
+		}
+	}
+
+	// Rebuilds the cloud a snapshot was taken of, failing with the invariant
+	// that the snapshot breaks if it was not taken of any
+	pub fn from_snapshot(snapshot: CloudSnapshot<T>) -> Result<Self, Error> {
+		let CloudSnapshot{backend, non_finite_policy, points, next_handle, skipped, hull} = snapshot;
+		let invalid = |what| Err(Error::InvalidSnapshot(what));
+		if !points.windows(2).all(|pair| pair[0].0 < pair[1].0) {
+			return invalid("point handles are not in increasing order");
+		}
+		let point_log = PointLog{
+			points: points.iter().cloned().collect(),
+			next_handle,
+			non_finite_policy,
+			skipped,
 		};
+		point_log.check().map_err(Error::InvalidSnapshot)?;
+
+		let hull = match ConvexPolygon::try_from_positions(hull) {
+			Some(hull) => hull,
+			None => return invalid("hull is not convex and counter-clockwise"),
+		};
+		let logged: Vec<Point<T>> = points.iter().map(|(_handle, p)| *p).collect();
+		check_hull(&hull, &logged).map_err(Error::InvalidSnapshot)?;
+
+		let mut cloud = Self::with_backend(backend);
+		for (handle, p) in points.into_iter() {
+			cloud.log(handle, p);
+		}
+		// The same points, with the handles and policy of the snapshot
+		cloud.point_log = point_log;
+		cloud.update_circle(true, None);
+		cloud.circle_changed = false;
+		Ok(cloud)
+	}
 
-		let find_farthest_pos = |ignores: HashSet<(u64, u64)>| {
-			let mut vertex = self.cover.some_vertex();
-			let mut best_pos: Option<Point> = None;
-			
-			for _i in 0..self.cover.degree() {
-				let pos = vertex.position();
-				vertex = vertex.fwd_vertex();
+	// Checks that the hull tracks exactly the logged points, is convex and
+	// counter-clockwise, and covers every point, and that so does the cover
+	// circle; fails with the first invariant broken, which indicates a bug
+	pub fn validate(&self) -> Result<(), Error> {
+		let points: Vec<Point<T>> = self.point_log.points.values().cloned().collect();
+		self.check(&points).map_err(Error::InconsistentState)?;
+		let in_bag = |(handle, p): (PointHandle, Point<T>)| self.positions.contains(handle, p);
+		if self.positions.len() != points.len() || !self.iter().all(in_bag) {
+			return Err(Error::InconsistentState("point bag and point log hold different points"));
+		}
 
-				if ignores.contains(&pos.to_bits()) {
-					continue;
+		match &self.circle {
+			None if points.is_empty() => Ok(()),
+			None => Err(Error::InconsistentState("non-empty cloud has no cover circle")),
+			Some((circle, support)) => {
+				if !support.iter().all(|s| points.contains(s)) {
+					return Err(Error::InconsistentState("support point is not a logged point"));
 				}
-				best_pos = match best_pos {
-					None => Some(pos),
-					Some(b_pos) => Some({
-						if (pos-center).sq_mag() > (b_pos-center).sq_mag() {
-							pos
-						} else {
-							b_pos
-						}
-					}),
-				};
-			}
+				if circle.is_some_and(|circle| !points.iter().all(|p| circle.covers(*p))) {
+					return Err(Error::InconsistentState("cover circle leaves out a logged point"));
+				}
+				Ok(())
+			},
+		}
+	}
 
-			best_pos
-		};
+	fn check(&self, points: &[Point<T>]) -> Result<(), &'static str> {
+		if !points.iter().all(|p| p.is_finite()) {
+			return Err("logged point has a non-finite coordinate");
+		}
 
-		let circ_pos1 = find_farthest_pos(HashSet::<(u64, u64)>::new()).unwrap();
-		let circ_pos2 = find_farthest_pos(
-			vec![circ_pos1]
-			.into_iter().map(|p| p.to_bits()).collect()
-		).unwrap();
-		let circ_2pt = ClosedCircle::from_two_points(circ_pos1, circ_pos2);
-
-		let circ_pos3 = find_farthest_pos(
-			vec![circ_pos1, circ_pos2]
-			.into_iter().map(|p| p.to_bits()).collect()
-		);
-
-		match circ_pos3 {
-			Some(p3) => {
-				if !circ_2pt.covers(p3) {
-					ClosedCircle::from_three_points(circ_pos1, circ_pos2, p3)
-				} else {
-					circ_2pt
+		match &self.cover {
+			Cover::Dynamic(hull) => {
+				let mut sorted = points.to_vec();
+				sorted.sort_by(cmp_lexicographic);
+				if hull.points() != sorted {
+					return Err("hull and point log hold different points");
 				}
+				let polygon = ConvexPolygon::try_from_positions(hull.vertices())
+					.ok_or("hull is not convex and counter-clockwise")?;
+				check_hull(&polygon, points)
 			},
-			None => circ_2pt,
+			Cover::Fifo(hull) => {
+				if hull.points() != points {
+					return Err("hull and point log hold different points");
+				}
+				// The queue's hull is that of the two stacks' hulls
+				for (polygon, stack) in hull.stacks().iter() {
+					if !polygon.is_convex() {
+						return Err("hull is not convex and counter-clockwise");
+					}
+					check_hull(polygon, stack)?;
+				}
+				Ok(())
+			},
+		}
+	}
+
+	// Validates the cloud after every update, with the `debug-invariants`
+	// feature
+	fn check_invariants(&self) {
+		#[cfg(feature = "debug-invariants")]
+		self.validate().unwrap_or_else(|e| panic!("{}", e));
+	}
+
+	// Points spanning the convex hull; every point on the smallest covering
+	// circle is among them
+	pub(crate) fn hull_vertices(&self) -> Vec<Point<T>> {
+		match &self.cover {
+			Cover::Dynamic(hull) => hull.vertices(),
+			Cover::Fifo(hull) => hull.vertices(),
 		}
 	}
+
+	// Vertices of the convex hull, counter-clockwise from the
+	// lexicographically smallest
+	fn hull(&self) -> Vec<Point<T>> {
+		ConvexPolygon::hull_of(&self.hull_vertices()).positions().to_vec()
+	}
+}
+
+// Points of the first hull missing from the second, in lexicographic order
+fn difference<T: Scalar>(hull: &[Point<T>], other: &[Point<T>]) -> Vec<Point<T>> {
+	let mut other = other.to_vec();
+	other.sort_by(cmp_lexicographic);
+	let mut points: Vec<Point<T>> = hull.iter()
+		.filter(|p| other.binary_search_by(|q| cmp_lexicographic(q, p)).is_err())
+		.cloned()
+		.collect();
+	points.sort_by(cmp_lexicographic);
+	points
+}
+
+// Whether the polygon is the hull of the given points: every vertex is one of
+// the points, and every point is covered
+fn check_hull<T: Scalar>(hull: &ConvexPolygon<T>, points: &[Point<T>]) -> Result<(), &'static str> {
+	let mut sorted = points.to_vec();
+	sorted.sort_by(cmp_lexicographic);
+	let is_logged = |v: &Point<T>| sorted.binary_search_by(|p| cmp_lexicographic(p, v)).is_ok();
+	if !hull.positions().iter().all(is_logged) {
+		return Err("hull vertex is not a logged point");
+	}
+	if !points.iter().all(|p| hull.covers(*p)) {
+		return Err("logged point lies outside the hull");
+	}
+	Ok(())
+}
+
+impl<T: Scalar> Default for MovingPointCloud<T> {
+	fn default() -> Self {
+		Self::with_backend(Backend::default())
+	}
+}
+
+impl<T: Scalar> TryFrom<CloudSnapshot<T>> for MovingPointCloud<T> {
+	type Error = Error;
+
+	fn try_from(snapshot: CloudSnapshot<T>) -> Result<Self, Error> {
+		Self::from_snapshot(snapshot)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<T: Scalar + serde::Serialize> serde::Serialize for MovingPointCloud<T> {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serde::Serialize::serialize(&self.snapshot(), serializer)
+	}
+}
+
+
+// Three-dimensional counterpart of `MovingPointCloud`. No hull is kept: the
+// sphere is cached with its support points, and found again from all the
+// points, in expected linear time, only when a point outside it arrives or
+// one of its support points leaves.
+pub struct MovingPointCloud3{
+	point_log: PointLog<Point3>,
+	// `None` for an empty cloud
+	sphere: Option<(ClosedSphere, Vec<Point3>)>,
+}
+
+impl MovingPointCloud3 {
+	pub fn new() -> Self {
+		Self{point_log: PointLog::new(), sphere: None}
+	}
+
+	pub fn with_non_finite_policy(mut self, policy: NonFinitePolicy) -> Self {
+		self.point_log.set_non_finite_policy(policy);
+		self
+	}
+
+	pub fn non_finite_policy(&self) -> NonFinitePolicy {
+		self.point_log.non_finite_policy
+	}
+
+	// Number of points skipped under the non-finite policy
+	pub fn skipped(&self) -> usize {
+		self.point_log.skipped
+	}
+
+	pub fn len(&self) -> usize {
+		self.point_log.points.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.point_log.points.is_empty()
+	}
+
+	pub fn extend<I: Iterator<Item=Point3>>(&mut self, iter: I) {
+		for p in iter {
+			self.push(p);
+		}
+	}
+
+	pub fn get(&self, handle: PointHandle) -> Option<Point3> {
+		self.point_log.points.get(&handle).cloned()
+	}
+
+	// Iterates over logged points, from oldest to newest
+	pub fn iter(&self) -> impl Iterator<Item=(PointHandle, Point3)> + '_ {
+		self.point_log.points.iter().map(|(h, p)| (*h, *p))
+	}
+
+	// Panics on points with non-finite coordinates, if the policy rejects
+	// them
+	pub fn push(&mut self, p: Point3) -> PointHandle {
+		self.try_push(p).unwrap_or_else(|e| panic!("{}", e))
+	}
+
+	// As `MovingPointCloud::try_push`
+	pub fn try_push(&mut self, p: Point3) -> Result<PointHandle, Error> {
+		let (handle, p) = self.point_log.admit(p)?;
+		if let Some(p) = p {
+			self.point_log.points.insert(handle, p);
+			if !self.sphere.as_ref().is_some_and(|(sphere, _support)| sphere.covers(p)) {
+				self.update_sphere();
+			}
+		}
+		Ok(handle)
+	}
+
+	// Removes the oldest point
+	pub fn pop(&mut self) -> Option<Point3> {
+		self.try_pop().ok()
+	}
+
+	pub fn try_pop(&mut self) -> Result<Point3, Error> {
+		let (handle, _p) = self.point_log.first()?;
+		self.try_remove(handle)
+	}
+
+	// Removes the point with the given handle, regardless of its age
+	pub fn remove(&mut self, handle: PointHandle) -> Option<Point3> {
+		self.try_remove(handle).ok()
+	}
+
+	// As `MovingPointCloud::try_remove`
+	pub fn try_remove(&mut self, handle: PointHandle) -> Result<Point3, Error> {
+		let p = self.point_log.points.remove(&handle).ok_or(Error::UnknownHandle(handle))?;
+		if self.sphere.as_ref().is_none_or(|(_sphere, support)| support.contains(&p)) {
+			self.update_sphere();
+		}
+		Ok(p)
+	}
+
+	fn update_sphere(&mut self) {
+		let points: Vec<Point3> = self.point_log.points.values().cloned().collect();
+		self.sphere = ClosedSphere::covering_with_support(&points);
+	}
+
+	// Panics if the cloud is empty
+	pub fn cover_sphere(&self) -> ClosedSphere {
+		self.try_cover_sphere().unwrap_or_else(|e| panic!("{}", e))
+	}
+
+	// `None` if the cloud is empty
+	pub fn checked_cover_sphere(&self) -> Option<ClosedSphere> {
+		self.try_cover_sphere().ok()
+	}
+
+	pub fn try_cover_sphere(&self) -> Result<ClosedSphere, Error> {
+		self.sphere.as_ref()
+			.map(|(sphere, _support)| *sphere)
+			.ok_or(Error::EmptyCloud)
+	}
+}
+
+impl Default for MovingPointCloud3 {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+// Serialized as its point log alone; the sphere is found again on restoring
+#[cfg(feature = "serde")]
+impl serde::Serialize for MovingPointCloud3 {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serde::Serialize::serialize(&self.point_log, serializer)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for MovingPointCloud3 {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let point_log: PointLog<Point3> = serde::Deserialize::deserialize(deserializer)?;
+		point_log.check().map_err(serde::de::Error::custom)?;
+		let mut cloud = Self{point_log, sphere: None};
+		cloud.update_sphere();
+		Ok(cloud)
+	}
 }
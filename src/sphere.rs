@@ -0,0 +1,259 @@
+use crate::points::{Point3, cmp_lexicographic3};
+use crate::predicates::sphere_excess;
+use crate::circle::{shuffled, farthest_pair_by, least_sq_radius};
+
+use std::cmp::Ordering;
+
+
+// Ball of all points within the radius of the center, boundary included
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClosedSphere {
+	pub center: Point3,
+	pub sq_radius: f64,
+}
+
+
+impl ClosedSphere {
+	pub fn new(center: Point3, radius: f64) -> Self {
+		Self {center, sq_radius: radius*radius}
+	}
+
+	pub fn from_point(p: Point3) -> Self {
+		Self {center: p, sq_radius: 0.}
+	}
+
+	pub fn from_two_points(p1: Point3, p2: Point3) -> Self {
+		Self {
+			center: p1 + (p2-p1)*0.5,
+			sq_radius: 0.25*(p2-p1).sq_mag()
+		}
+	}
+
+	// Smallest sphere through all three points, centered in their plane; the
+	// points must not be collinear (or coincide)
+	pub fn from_three_points(p1: Point3, p2: Point3, p3: Point3) -> Self {
+		let (b, c) = (p2-p1, p3-p1);
+		let normal = b.cross(c);
+		let offset = (c*b.sq_mag() - b*c.sq_mag()).cross(normal) * (0.5 / normal.sq_mag());
+		Self {center: p1 + offset, sq_radius: offset.sq_mag()}
+	}
+
+	pub fn try_from_three_points(p1: Point3, p2: Point3, p3: Point3) -> Option<Self> {
+		Some(Self::from_three_points(p1, p2, p3)).filter(|s| s.center.is_finite())
+	}
+
+	// Falls back to the sphere whose diameter spans the two farthest-apart
+	// points, which covers the third when all three are collinear
+	pub fn from_three_points_or_diameter(p1: Point3, p2: Point3, p3: Point3) -> Self {
+		Self::try_from_three_points(p1, p2, p3).unwrap_or_else(|| {
+			let (q1, q2) = farthest_pair_by(p1, p2, p3, |p, q1, q2| (q1-p).dot(q2-p) <= 0.);
+			Self::from_two_points(q1, q2)
+		})
+	}
+
+	// Sphere through all four points; they must not be coplanar
+	pub fn from_four_points(p1: Point3, p2: Point3, p3: Point3, p4: Point3) -> Self {
+		let (b, c, d) = (p2-p1, p3-p1, p4-p1);
+		let offset = (
+			c.cross(d)*b.sq_mag()
+			+ d.cross(b)*c.sq_mag()
+			+ b.cross(c)*d.sq_mag()
+		) * (0.5 / b.dot(c.cross(d)));
+		Self {center: p1 + offset, sq_radius: offset.sq_mag()}
+	}
+
+	pub fn try_from_four_points(p1: Point3, p2: Point3, p3: Point3, p4: Point3) -> Option<Self> {
+		Some(Self::from_four_points(p1, p2, p3, p4)).filter(|s| s.center.is_finite())
+	}
+
+	// Falls back to the smallest sphere through three of the points that
+	// covers the fourth, which exists when all four lie on a circle (or else
+	// the largest, if rounding error leaves none covering it)
+	pub fn from_four_points_or_fewer(p1: Point3, p2: Point3, p3: Point3, p4: Point3) -> Self {
+		Self::try_from_four_points(p1, p2, p3, p4).unwrap_or_else(|| {
+			let by_size = |s1: &Self, s2: &Self| {
+				s1.sq_radius.partial_cmp(&s2.sq_radius).unwrap_or(Ordering::Equal)
+			};
+			let candidates = [
+				(Self::from_three_points_or_diameter(p2, p3, p4), p1),
+				(Self::from_three_points_or_diameter(p1, p3, p4), p2),
+				(Self::from_three_points_or_diameter(p1, p2, p4), p3),
+				(Self::from_three_points_or_diameter(p1, p2, p3), p4),
+			];
+			candidates.iter()
+				.filter(|(sphere, p)| sphere.covers(*p))
+				.map(|(sphere, _p)| *sphere)
+				.min_by(by_size)
+				.or_else(|| candidates.iter().map(|(sphere, _p)| *sphere).max_by(by_size))
+				.unwrap()
+		})
+	}
+
+	// Decided exactly, for the sphere as stored
+	pub fn covers(&self, point: Point3) -> bool {
+		sphere_excess(self.center, self.sq_radius, point) <= 0.
+	}
+
+	pub fn radius(&self) -> f64 {
+		f64::sqrt(self.sq_radius)
+	}
+
+	// Smallest sphere covering every point, via Welzl's algorithm (iterative
+	// form, over a fixed pseudo-random permutation of the input). Unlike
+	// `ClosedCircle::covering`, support points are found in floating point,
+	// so in degenerate cases the result may be larger than it need be by a
+	// rounding error; it still covers every point exactly.
+	pub fn covering(points: &[Point3]) -> Option<Self> {
+		Self::covering_with_support(points).map(|(sphere, _support)| sphere)
+	}
+
+	// As `covering`, also returning the support points the sphere is built
+	// from, in lexicographic order
+	pub(crate) fn covering_with_support(points: &[Point3]) -> Option<(Self, Vec<Point3>)> {
+		let order = shuffled(points);
+		let mut support = vec![*order.first()?];
+		let mut sphere = Self::from_point(support[0]);
+
+		for i in 1..order.len() {
+			if sphere.covers(order[i]) {
+				continue;
+			}
+			support = vec![order[i]];
+			sphere = Self::from_support(&support);
+			for j in 0..i {
+				if sphere.covers(order[j]) {
+					continue;
+				}
+				support = vec![order[i], order[j]];
+				sphere = Self::from_support(&support);
+				for k in 0..j {
+					if sphere.covers(order[k]) {
+						continue;
+					}
+					support = vec![order[i], order[j], order[k]];
+					sphere = Self::from_support(&support);
+					for l in 0..k {
+						if sphere.covers(order[l]) {
+							continue;
+						}
+						support = vec![order[i], order[j], order[k], order[l]];
+						sphere = Self::from_support(&support);
+					}
+				}
+			}
+		}
+
+		// Build from the support points in a canonical order, so that the
+		// result does not depend on the order the points were given in
+		support.sort_by(cmp_lexicographic3);
+		let mut sphere = Self::from_support(&support);
+
+		// Absorb any rounding error, so that `covers` holds for every point
+		let center = sphere.center;
+		for p in points.iter() {
+			if !sphere.covers(*p) {
+				sphere.sq_radius = sphere.sq_radius.max(sq_radius_reaching(center, *p));
+			}
+		}
+
+		Some((sphere, support))
+	}
+
+	fn from_support(support: &[Point3]) -> Self {
+		match *support {
+			[p1] => Self::from_point(p1),
+			[p1, p2] => Self::from_two_points(p1, p2),
+			[p1, p2, p3] => Self::from_three_points_or_diameter(p1, p2, p3),
+			[p1, p2, p3, p4] => Self::from_four_points_or_fewer(p1, p2, p3, p4),
+			_ => unreachable!(),
+		}
+	}
+}
+
+
+// Smallest squared radius of a sphere about `center` that covers `p`
+fn sq_radius_reaching(center: Point3, p: Point3) -> f64 {
+	least_sq_radius((p - center).sq_mag(), |sq_radius| sphere_excess(center, sq_radius, p).partial_cmp(&0.))
+		.unwrap_or(f64::INFINITY)
+}
+
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+	use super::{ClosedSphere, Point3};
+
+	fn p(x: f64, y: f64, z: f64) -> Point3 {
+		Point3{x, y, z}
+	}
+
+	#[test]
+	fn from_two_points() {
+		assert_eq!(
+			ClosedSphere::from_two_points(p(4., -1., 2.), p(0., -1., 2.)),
+			ClosedSphere{center: p(2., -1., 2.), sq_radius: 4.},
+		);
+	}
+
+	#[test]
+	fn from_three_points() {
+		// Right angle at the origin, so the hypotenuse is a diameter
+		let sphere = ClosedSphere::from_three_points(p(2., 0., 0.), p(0., 0., 0.), p(0., 2., 0.));
+		assert_eq!(sphere, ClosedSphere{center: p(1., 1., 0.), sq_radius: 2.});
+
+		assert_eq!(ClosedSphere::try_from_three_points(p(0., 0., 0.), p(1., 1., 1.), p(2., 2., 2.)), None);
+		assert_eq!(
+			ClosedSphere::from_three_points_or_diameter(p(0., 0., 0.), p(2., 2., 2.), p(1., 1., 1.)),
+			ClosedSphere::from_two_points(p(0., 0., 0.), p(2., 2., 2.)),
+		);
+	}
+
+	#[test]
+	fn from_four_points() {
+		let sphere = ClosedSphere::from_four_points(
+			p(1., 0., 0.), p(-1., 0., 0.), p(0., 1., 0.), p(0., 0., -1.),
+		);
+		assert_eq!(sphere, ClosedSphere{center: p(0., 0., 0.), sq_radius: 1.});
+
+		// Cocircular points have no single sphere through them
+		let (a, b, c, d) = (p(1., 0., 0.), p(-1., 0., 0.), p(0., 1., 0.), p(0., -1., 0.));
+		assert_eq!(ClosedSphere::try_from_four_points(a, b, c, d), None);
+		let sphere = ClosedSphere::from_four_points_or_fewer(a, b, c, d);
+		assert!([a, b, c, d].iter().all(|q| sphere.covers(*q)));
+		assert_eq!(sphere.radius(), 1.);
+	}
+
+	#[test]
+	fn covering() {
+		assert_eq!(ClosedSphere::covering(&[]), None);
+
+		// Octahedron, with interior points
+		let points = [
+			p(1., 0., 0.), p(-1., 0., 0.), p(0., 1., 0.),
+			p(0., -1., 0.), p(0., 0., 1.), p(0., 0., -1.),
+			p(0.5, 0.5, 0.5), p(0., 0., 0.),
+		];
+		let sphere = ClosedSphere::covering(&points).unwrap();
+		assert_eq!(sphere.center, p(0., 0., 0.));
+		assert_eq!(sphere.radius(), 1.);
+
+		// Order of points shouldn't change result
+		let mut reversed = points;
+		reversed.reverse();
+		assert_eq!(ClosedSphere::covering(&reversed), Some(sphere));
+
+		// Collinear points
+		let sphere = ClosedSphere::covering(&[p(0., 0., 0.), p(3., 3., 3.), p(1., 1., 1.)]).unwrap();
+		assert_eq!(sphere, ClosedSphere::from_two_points(p(0., 0., 0.), p(3., 3., 3.)));
+	}
+
+	#[test]
+	fn covers() {
+		let sphere = ClosedSphere{center: p(2., -1., 0.), sq_radius: 4.};
+		assert!(sphere.covers(p(2., -1., 2.)));
+		assert!(sphere.covers(p(1., 0., 1.)));
+		assert!(!sphere.covers(p(0., 0., 0.5)));
+	}
+}
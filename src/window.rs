@@ -0,0 +1,447 @@
+use crate::points::Point;
+use crate::scalar::Scalar;
+use crate::circle::ClosedCircle;
+use crate::smallest_cover::{Backend, CircleChange, MovingPointCloud, NonFinitePolicy};
+use crate::error::Error;
+
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::ops::Sub;
+
+
+// Point cloud over a sliding window of timestamped points, where `T` is any
+// ordered key (e.g. `Instant`, or seconds as `u64`/`f64`), `D` is the span
+// between two keys (e.g. `Duration`), and `S` is the scalar type of the points.
+// With the `serde` feature, a window that could not have arisen from pushes
+// fails to deserialize, as does its cloud.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(
+	try_from = "WindowCheckpoint<T, D, S>",
+	bound(
+		serialize = "T: serde::Serialize, D: serde::Serialize, S: Scalar + serde::Serialize",
+		deserialize = "T: Copy + PartialOrd + Sub<Output=D> + serde::Deserialize<'de>, \
+			D: PartialOrd + serde::Deserialize<'de>, S: Scalar + serde::Deserialize<'de>",
+	),
+))]
+pub struct TimedPointCloud<T, D = T, S = f64> {
+	points: MovingPointCloud<S>,
+	timestamps: VecDeque<T>,
+	max_count: Option<usize>,
+	max_age: Option<D>,
+	circle_changed: bool,
+}
+
+impl<T, D, S> TimedPointCloud<T, D, S>
+where
+	T: Copy + PartialOrd + Sub<Output=D>,
+	D: PartialOrd,
+	S: Scalar,
+{
+	pub fn new() -> Self {
+		Self{
+			points: MovingPointCloud::default(),
+			timestamps: VecDeque::new(),
+			max_count: None,
+			max_age: None,
+			circle_changed: false,
+		}
+	}
+
+	// Points only ever leave the window oldest first, which suits
+	// `Backend::Fifo`
+	pub fn with_backend(self, backend: Backend) -> Self {
+		assert!(self.is_empty(), "backend must be chosen before pushing points");
+		Self{points: self.points.on_backend(backend), ..self}
+	}
+
+	pub fn with_non_finite_policy(self, policy: NonFinitePolicy) -> Self {
+		Self{points: self.points.with_non_finite_policy(policy), ..self}
+	}
+
+	// As `MovingPointCloud::with_observer`; each point pushed or evicted is
+	// a separate update
+	pub fn with_observer<F: FnMut(&CircleChange<S>) + Send + Sync + 'static>(self, observer: F) -> Self {
+		Self{points: self.points.with_observer(observer), ..self}
+	}
+
+	pub fn with_max_count(self, max_count: usize) -> Self {
+		Self{max_count: Some(max_count), ..self}
+	}
+
+	pub fn with_max_age(self, max_age: D) -> Self {
+		Self{max_age: Some(max_age), ..self}
+	}
+
+	pub fn len(&self) -> usize {
+		self.timestamps.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.timestamps.is_empty()
+	}
+
+	// Number of points skipped under the non-finite policy
+	pub fn skipped(&self) -> usize {
+		self.points.skipped()
+	}
+
+	pub fn oldest(&self) -> Option<T> {
+		self.timestamps.front().cloned()
+	}
+
+	pub fn newest(&self) -> Option<T> {
+		self.timestamps.back().cloned()
+	}
+
+	// Timestamps must not decrease from one push to the next; panics if they
+	// do, or on points with non-finite coordinates if the policy rejects them
+	pub fn push(&mut self, time: T, p: Point<S>) {
+		self.try_push(time, p).unwrap_or_else(|e| panic!("{}", e))
+	}
+
+	// Fails on out-of-order timestamps, or points with non-finite coordinates
+	// if the policy rejects them, leaving the window unchanged. A skipped
+	// point still advances the time, expiring older points.
+	pub fn try_push(&mut self, time: T, p: Point<S>) -> Result<(), Error> {
+		let in_order = |newest: T| matches!(
+			newest.partial_cmp(&time),
+			Some(Ordering::Less) | Some(Ordering::Equal)
+		);
+		if self.newest().is_some_and(|newest| !in_order(newest)) {
+			return Err(Error::TimestampOutOfOrder);
+		}
+		let circle = self.cover_circle();
+		let (_handle, logged) = self.points.push_sanitized(p)?;
+		if logged {
+			self.timestamps.push_back(time);
+		}
+
+		if let Some(max_count) = self.max_count {
+			while self.len() > max_count {
+				self.pop();
+			}
+		}
+		self.expire_older_than(time);
+		self.circle_changed = self.cover_circle() != circle;
+		Ok(())
+	}
+
+	pub fn extend<I: Iterator<Item=(T, Point<S>)>>(&mut self, iter: I) {
+		for (t, p) in iter {
+			self.push(t, p);
+		}
+	}
+
+	// Evicts all points timestamped strictly before `time`
+	pub fn expire_before(&mut self, time: T) {
+		let circle = self.cover_circle();
+		while self.oldest().is_some_and(|t| t < time) {
+			self.pop();
+		}
+		self.circle_changed = self.cover_circle() != circle;
+	}
+
+	// Evicts all points more than the maximum age older than `now`. The
+	// window only ages on pushes, so a window that is queried without new
+	// points arriving should be brought up to date with this first.
+	pub fn expire(&mut self, now: T) {
+		let circle = self.cover_circle();
+		self.expire_older_than(now);
+		self.circle_changed = self.cover_circle() != circle;
+	}
+
+	fn expire_older_than(&mut self, time: T) {
+		while self.oldest().is_some_and(|t| self.is_too_old(t, time)) {
+			self.pop();
+		}
+	}
+
+	// Points timestamped at or after `time` are never too old, which also
+	// keeps unsigned timestamps from underflowing
+	fn is_too_old(&self, t: T, time: T) -> bool {
+		self.max_age.as_ref().is_some_and(|max_age| t < time && time - t > *max_age)
+	}
+
+	fn pop(&mut self) {
+		self.timestamps.pop_front();
+		self.points.pop();
+	}
+
+	// Whether the last push or expiry changed the cover circle, counting the
+	// points it evicted along with any it pushed
+	pub fn circle_changed(&self) -> bool {
+		self.circle_changed
+	}
+
+	// Circle covering the points live as of the last push or expiry; points
+	// that have since outgrown the maximum age stay until `expire(now)`.
+	// `None` if the window is empty, or its circle overflows the scalar type.
+	pub fn cover_circle(&self) -> Option<ClosedCircle<S>> {
+		self.points.checked_cover_circle()
+	}
+
+	pub fn try_cover_circle(&self) -> Result<ClosedCircle<S>, Error> {
+		self.points.try_cover_circle()
+	}
+}
+
+impl<T, D, S> Default for TimedPointCloud<T, D, S>
+where
+	T: Copy + PartialOrd + Sub<Output=D>,
+	D: PartialOrd,
+	S: Scalar,
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+// Fields of a `TimedPointCloud` as deserialized, before they are checked
+// against each other
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(bound(deserialize = "T: serde::Deserialize<'de>, D: serde::Deserialize<'de>, S: Scalar + serde::Deserialize<'de>"))]
+struct WindowCheckpoint<T, D, S> {
+	points: MovingPointCloud<S>,
+	timestamps: VecDeque<T>,
+	max_count: Option<usize>,
+	max_age: Option<D>,
+	circle_changed: bool,
+}
+
+#[cfg(feature = "serde")]
+impl<T, D, S> std::convert::TryFrom<WindowCheckpoint<T, D, S>> for TimedPointCloud<T, D, S>
+where
+	T: Copy + PartialOrd + Sub<Output=D>,
+	D: PartialOrd,
+	S: Scalar,
+{
+	type Error = Error;
+
+	fn try_from(checkpoint: WindowCheckpoint<T, D, S>) -> Result<Self, Error> {
+		let WindowCheckpoint{points, timestamps, max_count, max_age, circle_changed} = checkpoint;
+		let invalid = |what| Err(Error::InvalidSnapshot(what));
+		if timestamps.len() != points.len() {
+			return invalid("window holds a different number of timestamps and points");
+		}
+		let in_order = |t1: &T, t2: &T| matches!(t1.partial_cmp(t2), Some(Ordering::Less) | Some(Ordering::Equal));
+		if !timestamps.iter().zip(timestamps.iter().skip(1)).all(|(t1, t2)| in_order(t1, t2)) {
+			return invalid("timestamps are not in increasing order");
+		}
+		if max_count.is_some_and(|max_count| timestamps.len() > max_count) {
+			return invalid("window holds more points than its maximum count");
+		}
+
+		let window = Self{points, timestamps, max_count, max_age, circle_changed};
+		if window.newest().is_some_and(|newest| window.oldest().is_some_and(|t| window.is_too_old(t, newest))) {
+			return invalid("window holds points older than its maximum age");
+		}
+		Ok(window)
+	}
+}
+
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+	use super::{TimedPointCloud, Backend, NonFinitePolicy, Error, Point};
+	use std::time::{Duration, Instant};
+
+	fn unit_point(i: u64) -> Point {
+		let theta = (i as f64).to_radians() * 90.;
+		Point{x: theta.cos(), y: theta.sin()}
+	}
+
+	#[test]
+	fn unbounded() {
+		let mut cloud = TimedPointCloud::<u64>::new();
+		cloud.extend((0..8).map(|i| (i, unit_point(i))));
+
+		assert_eq!(cloud.len(), 8);
+		assert_eq!(cloud.oldest(), Some(0));
+		assert_eq!(cloud.newest(), Some(7));
+	}
+
+	#[test]
+	fn expire_before() {
+		let mut cloud = TimedPointCloud::<u64>::new();
+		cloud.push(0, Point{x: -1., y: 0.});
+		cloud.push(2, Point{x: 1., y: 0.});
+		cloud.push(2, Point{x: 0., y: 0.5});
+		cloud.push(5, Point{x: 0., y: 0.});
+
+		cloud.expire_before(2);
+		assert_eq!(cloud.len(), 3);
+		assert_eq!(cloud.cover_circle().map(|c| c.sq_radius), Some(0.25 * 1.25));
+
+		cloud.expire_before(3);
+		assert_eq!(cloud.len(), 1);
+		assert_eq!(cloud.cover_circle().map(|c| c.radius()), Some(0.));
+
+		cloud.expire_before(6);
+		assert!(cloud.is_empty());
+		assert_eq!(cloud.oldest(), None);
+	}
+
+	#[test]
+	fn max_count() {
+		let mut cloud = TimedPointCloud::<u64>::new().with_max_count(3);
+		cloud.extend((0..8).map(|i| (i, unit_point(i))));
+
+		assert_eq!(cloud.len(), 3);
+		assert_eq!(cloud.oldest(), Some(5));
+		assert_eq!(cloud.newest(), Some(7));
+	}
+
+	#[test]
+	fn max_age() {
+		let mut cloud = TimedPointCloud::<f64>::new().with_max_age(2.5);
+		cloud.extend((0..8).map(|i| (i as f64, unit_point(i))));
+
+		assert_eq!(cloud.len(), 3);
+		assert_eq!(cloud.oldest(), Some(5.));
+		assert_eq!(cloud.newest(), Some(7.));
+
+		// A point exactly at the maximum age is still live
+		cloud.push(7.5, Point{x: 0., y: 0.});
+		assert_eq!(cloud.len(), 4);
+		assert_eq!(cloud.oldest(), Some(5.));
+	}
+
+	#[test]
+	fn expire() {
+		let mut cloud = TimedPointCloud::<u64>::new().with_max_age(2);
+		cloud.push(0, Point{x: -1., y: 0.});
+		cloud.push(1, Point{x: 1., y: 0.});
+		cloud.push(2, Point{x: 0., y: 0.});
+		assert_eq!(cloud.len(), 3);
+
+		// Without a push, points outlive the maximum age until expired
+		cloud.expire(3);
+		assert_eq!(cloud.len(), 2);
+		assert!(cloud.circle_changed());
+		assert_eq!(cloud.cover_circle().map(|c| c.sq_radius), Some(0.25));
+		cloud.expire(4);
+		assert_eq!(cloud.oldest(), Some(2));
+		cloud.expire(4);
+		assert!(!cloud.circle_changed());
+		assert_eq!(cloud.len(), 1);
+	}
+
+	#[test]
+	fn expire_before_oldest() {
+		let mut cloud = TimedPointCloud::<u64>::new().with_max_age(10);
+		cloud.push(5, Point{x: 0., y: 0.});
+		cloud.expire(3);
+		assert_eq!(cloud.len(), 1);
+		assert!(!cloud.circle_changed());
+		cloud.expire(16);
+		assert!(cloud.is_empty());
+	}
+
+	#[test]
+	fn max_age_duration() {
+		let t0 = Instant::now();
+		let mut cloud = TimedPointCloud::new()
+			.with_max_age(Duration::from_secs(60));
+		cloud.push(t0, Point{x: 0., y: 0.});
+		cloud.push(t0 + Duration::from_secs(30), Point{x: 1., y: 0.});
+		assert_eq!(cloud.len(), 2);
+
+		cloud.push(t0 + Duration::from_secs(90), Point{x: 2., y: 0.});
+		assert_eq!(cloud.len(), 2);
+		assert_eq!(cloud.cover_circle().map(|c| c.radius()), Some(0.5));
+	}
+
+	#[test]
+	fn fifo_backend() {
+		let mut cloud = TimedPointCloud::<u64>::new()
+			.with_backend(Backend::Fifo)
+			.with_max_count(3);
+		cloud.extend((0..8).map(|i| (i, unit_point(i))));
+		let mut reference = TimedPointCloud::<u64>::new().with_max_count(3);
+		reference.extend((0..8).map(|i| (i, unit_point(i))));
+
+		assert_eq!(cloud.len(), 3);
+		assert_eq!(cloud.oldest(), Some(5));
+		assert_eq!(cloud.cover_circle(), reference.cover_circle());
+	}
+
+	#[test]
+	fn builder_order() {
+		use std::sync::mpsc;
+
+		let (sender, changes) = mpsc::channel();
+		let mut cloud = TimedPointCloud::<u64>::new()
+			.with_observer(move |change| sender.send(change.new_circle).unwrap())
+			.with_non_finite_policy(NonFinitePolicy::Skip)
+			.with_backend(Backend::Fifo);
+		cloud.push(0, Point{x: 0., y: 0.});
+		cloud.push(1, Point{x: f64::NAN, y: 0.});
+		assert_eq!(cloud.skipped(), 1);
+		assert_eq!(changes.try_iter().collect::<Vec<_>>(), vec![cloud.cover_circle()]);
+	}
+
+	#[test]
+	fn try_push() {
+		let mut cloud = TimedPointCloud::<u64>::new();
+		assert_eq!(cloud.try_cover_circle(), Err(Error::EmptyCloud));
+		assert_eq!(cloud.try_push(2, Point{x: 0., y: 0.}), Ok(()));
+
+		assert_eq!(cloud.try_push(1, Point{x: 1., y: 0.}), Err(Error::TimestampOutOfOrder));
+		let p = Point{x: f64::INFINITY, y: 0.};
+		assert_eq!(cloud.try_push(3, p), Err(Error::NonFinitePoint(p)));
+		assert!(cloud.try_push(3, Point{x: f64::NAN, y: 0.}).is_err());
+		assert_eq!(cloud.len(), 1);
+		assert_eq!(cloud.newest(), Some(2));
+		assert_eq!(cloud.try_cover_circle().map(|c| c.center), Ok(Point{x: 0., y: 0.}));
+	}
+
+	#[test]
+	fn skip_non_finite() {
+		let mut cloud = TimedPointCloud::<u64>::new()
+			.with_non_finite_policy(NonFinitePolicy::Skip)
+			.with_max_age(1);
+		cloud.push(0, Point{x: 0., y: 0.});
+		cloud.push(1, Point{x: 1., y: 0.});
+		cloud.push(2, Point{x: f64::NAN, y: 0.});
+
+		// The skipped point is not logged, but still expires the oldest
+		assert_eq!(cloud.len(), 1);
+		assert_eq!(cloud.skipped(), 1);
+		assert_eq!(cloud.oldest(), Some(1));
+		assert_eq!(cloud.newest(), Some(1));
+		assert_eq!(cloud.cover_circle().map(|c| c.radius()), Some(0.));
+
+		cloud.expire_before(2);
+		assert_eq!(cloud.cover_circle(), None);
+	}
+
+	#[test]
+	fn circle_changed() {
+		let mut cloud = TimedPointCloud::<u64>::new().with_max_age(2);
+		cloud.push(0, Point{x: 0., y: 0.});
+		cloud.push(1, Point{x: 2., y: 0.});
+		assert!(cloud.circle_changed());
+		cloud.push(2, Point{x: 1., y: 0.});
+		assert!(!cloud.circle_changed());
+
+		// Pushed point is covered, but the expired one was a support point
+		cloud.push(3, Point{x: 1.5, y: 0.});
+		assert!(cloud.circle_changed());
+		assert_eq!(cloud.len(), 3);
+		cloud.expire_before(3);
+		assert!(cloud.circle_changed());
+		cloud.expire_before(3);
+		assert!(!cloud.circle_changed());
+	}
+
+	#[test]
+	#[should_panic]
+	fn push_out_of_order() {
+		let mut cloud = TimedPointCloud::<u64>::new();
+		cloud.push(2, Point{x: 0., y: 0.});
+		cloud.push(1, Point{x: 1., y: 0.});
+	}
+}
@@ -0,0 +1,48 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+
+fn run(args: &[&str], input: &str) -> (bool, String) {
+	let mut child = Command::new(env!("CARGO_BIN_EXE_smallest-circle"))
+		.args(args)
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::null())
+		.spawn()
+		.unwrap();
+	child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+
+	let output = child.wait_with_output().unwrap();
+	(output.status.success(), String::from_utf8(output.stdout).unwrap())
+}
+
+#[test]
+fn whole_input() {
+	assert_eq!(
+		run(&["--format", "csv", "--output", "json"], "x,y\n0,0\n2,0\n1,0.5\n"),
+		(true, "{\"x\": 1, \"y\": 0, \"radius\": 1}\n".to_string()),
+	);
+	assert_eq!(run(&[], ""), (false, String::new()));
+	assert_eq!(run(&["-"], "0 0 1"), (false, String::new()));
+}
+
+#[test]
+fn json_members() {
+	let input = r#"[
+		{"x": 0, "y": 0, "id": "a,\"b\"", "tag": null},
+		{"meta": {"seen": [1, true, {"by": "c"}]}, "x": 2, "y": 0}
+	]"#;
+	assert_eq!(
+		run(&["--format", "json", "--output", "csv"], input),
+		(true, "x,y,radius\n1,0,1\n".to_string()),
+	);
+	assert_eq!(run(&["--format", "json"], r#"[{"x": 0, "y": 0, "ok": nope}]"#), (false, String::new()));
+}
+
+#[test]
+fn window() {
+	assert_eq!(
+		run(&["--window", "2", "-o", "csv"], "0 0\n2 0\n4 0\n"),
+		(true, "x,y,radius\n0,0,0\n1,0,1\n3,0,1\n".to_string()),
+	);
+}
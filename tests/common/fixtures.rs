@@ -0,0 +1,18 @@
+// Points and backends shared by the integration tests
+use smallest_circle::points::Point;
+use smallest_circle::smallest_cover::Backend;
+
+
+pub const BACKENDS: [Backend; 2] = [Backend::Dynamic, Backend::Fifo];
+
+// The `i`th of the integers below `modulus` in a scattered order, which
+// repeats after `modulus` of them (for `modulus` coprime to 37)
+pub fn scattered(i: u64, modulus: u64) -> u64 {
+	(i*37 + 11) % modulus
+}
+
+// Scattered along a curve, in general position
+pub fn wavy_point(i: u64) -> Point {
+	let i = scattered(i, 101);
+	Point{x: (i as f64 * 0.37).sin() * 10., y: (i as f64 * 0.91).cos() * 3.}
+}
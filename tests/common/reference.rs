@@ -0,0 +1,39 @@
+// Brute-force reference for the smallest enclosing circle, shared by the
+// integration tests and the fuzz target
+use smallest_circle::points::{Point, Vector};
+
+
+// Smallest circle through two or three of the points that covers them all,
+// allowing itself some rounding error; takes O(n⁴) time
+pub fn brute_force_sq_radius(points: &[Point]) -> f64 {
+	let sq_dist = |p1: Point, p2: Point| (p2-p1).sq_mag();
+	let covers_all = |center: Point, sq_radius: f64| points.iter()
+		.all(|p| sq_dist(center, *p) <= sq_radius * (1. + 1e-9) + 1e-12);
+
+	let mut best = if points.len() == 1 {0.} else {f64::INFINITY};
+	let mut consider = |center: Point, sq_radius: f64| {
+		if sq_radius < best && covers_all(center, sq_radius) {
+			best = sq_radius;
+		}
+	};
+	for (i, p1) in points.iter().enumerate() {
+		for (j, p2) in points.iter().enumerate().skip(i+1) {
+			let center = *p1 + (*p2-*p1)*0.5;
+			consider(center, sq_dist(center, *p1));
+
+			for p3 in points.iter().skip(j+1) {
+				let (v2, v3) = (*p2-*p1, *p3-*p1);
+				let d = 2. * v2.cross(v3);
+				if d == 0. {
+					continue;
+				}
+				let center = *p1 + Vector{
+					x: (v3.y*v2.sq_mag() - v2.y*v3.sq_mag()) / d,
+					y: (v2.x*v3.sq_mag() - v3.x*v2.sq_mag()) / d,
+				};
+				consider(center, sq_dist(center, *p1));
+			}
+		}
+	}
+	best
+}
@@ -1,6 +1,16 @@
 use smallest_circle::smallest_cover;
 use smallest_circle::points::Point;
 
+use fixtures::{BACKENDS, wavy_point};
+use reference::brute_force_sq_radius;
+
+use proptest::prelude::*;
+
+#[path = "common/fixtures.rs"]
+mod fixtures;
+#[path = "common/reference.rs"]
+mod reference;
+
 
 #[test]
 fn point_cloud_use() {
@@ -59,3 +69,891 @@ fn point_cloud_use() {
 	assert_eq!(point_cloud.cover_circle().radius(), 0.);
 	assert_eq!(point_cloud.len(), 1);
 }
+
+#[test]
+fn collinear_point_cloud() {
+	for scale in [1., 0.5, 0.1, 1e-3].iter() {
+		let points: Vec<Point> = [4, 0, 9, 2, 7, 1, 8, 3, 6, 5].iter()
+			.map(|i| Point{x: scale * *i as f64, y: 3. * scale * *i as f64 - 2.})
+			.collect();
+		let mut point_cloud = smallest_cover::MovingPointCloud::new();
+		point_cloud.extend(points.iter().cloned());
+
+		let circle = point_cloud.cover_circle();
+		assert!(circle.center.x.is_finite() && circle.center.y.is_finite());
+		assert!(circle.sq_radius.is_finite());
+		for p in points.iter() {
+			assert!(circle.covers(*p));
+		}
+	}
+}
+
+#[test]
+fn duplicate_points() {
+	let mut point_cloud = smallest_cover::MovingPointCloud::new();
+	let a = Point{x: -1., y: 0.};
+	let b = Point{x: 1., y: 0.};
+	let c = Point{x: 0., y: 0.5};
+
+	// Repeat hull vertices and interior points alike
+	point_cloud.extend(vec!(a, a, b, c, c, b, a, c).into_iter());
+	assert_eq!(point_cloud.len(), 8);
+	assert_eq!(point_cloud.cover_circle().radius(), 1.);
+
+	// The newer copies of `a` and `b` outlive the older ones, so the circle
+	// stays as it is
+	for len in (3..8).rev() {
+		point_cloud.pop();
+		assert_eq!(point_cloud.len(), len);
+		assert_eq!(point_cloud.cover_circle().radius(), 1.);
+		assert!(!point_cloud.circle_changed());
+	}
+
+	// Last copy of `b` leaves only `a` and `c`
+	point_cloud.pop();
+	assert!(point_cloud.circle_changed());
+	assert_eq!(point_cloud.len(), 2);
+	assert_eq!(point_cloud.cover_circle(), point_cloud_of(&[a, c]).cover_circle());
+
+	point_cloud.pop();
+	assert_eq!(point_cloud.len(), 1);
+	assert_eq!(point_cloud.cover_circle().center, c);
+	assert_eq!(point_cloud.cover_circle().radius(), 0.);
+
+	point_cloud.pop();
+	assert_eq!(point_cloud.len(), 0);
+
+	// Removing either copy of a support point by handle leaves the other
+	for backend in BACKENDS.iter().cloned() {
+		let mut point_cloud = smallest_cover::MovingPointCloud::with_backend(backend);
+		let handles: Vec<_> = [a, b, b, c].iter().map(|p| point_cloud.push(*p)).collect();
+		assert_eq!(point_cloud.remove(handles[2]), Some(b));
+		assert!(!point_cloud.circle_changed());
+		assert_eq!(point_cloud.remove(handles[1]), Some(b));
+		assert!(point_cloud.circle_changed());
+		assert_eq!(point_cloud.validate(), Ok(()));
+	}
+}
+
+#[test]
+fn remove_by_handle() {
+	let mut point_cloud = smallest_cover::MovingPointCloud::new();
+	let a = point_cloud.push(Point{x: -1., y: 0.});
+	let b = point_cloud.push(Point{x: 1., y: 0.});
+	let c = point_cloud.push(Point{x: 0., y: 3.});
+	let d = point_cloud.push(Point{x: 0., y: 0.5});
+	let e = point_cloud.push(Point{x: 0., y: -0.5});
+	assert_eq!(point_cloud.get(c), Some(Point{x: 0., y: 3.}));
+
+	// Retract the bad measurement out of order
+	assert_eq!(point_cloud.remove(c), Some(Point{x: 0., y: 3.}));
+	assert_eq!(point_cloud.remove(c), None);
+	assert_eq!(point_cloud.get(c), None);
+	assert_eq!(point_cloud.len(), 4);
+	assert_eq!(point_cloud.cover_circle().radius(), 1.);
+	assert_eq!(
+		point_cloud.iter().map(|(h, _p)| h).collect::<Vec<_>>(),
+		vec!(a, b, d, e),
+	);
+
+	// Remove interior and hull points alike
+	assert_eq!(point_cloud.remove(d), Some(Point{x: 0., y: 0.5}));
+	assert_eq!(point_cloud.remove(b), Some(Point{x: 1., y: 0.}));
+	assert_eq!(point_cloud.len(), 2);
+	assert_eq!(point_cloud.cover_circle(), point_cloud_of(&[
+		Point{x: -1., y: 0.}, Point{x: 0., y: -0.5}
+	]).cover_circle());
+
+	// Handles are not reused
+	let f = point_cloud.push(Point{x: 1., y: 0.});
+	assert!(f != b);
+	assert_eq!(point_cloud.pop(), Some(Point{x: -1., y: 0.}));
+	assert_eq!(point_cloud.remove(a), None);
+}
+
+fn point_cloud_of(points: &[Point]) -> smallest_cover::MovingPointCloud {
+	let mut point_cloud = smallest_cover::MovingPointCloud::new();
+	point_cloud.extend(points.iter().cloned());
+	point_cloud
+}
+
+
+proptest! {
+	#[test]
+	fn cover_circle_is_optimal(
+		coords in prop::collection::vec((-100_f64..100., -100_f64..100.), 1..12)
+	) {
+		let points: Vec<Point> = coords.into_iter()
+			.map(|(x, y)| Point{x, y})
+			.collect();
+		let mut point_cloud = smallest_cover::MovingPointCloud::new();
+		point_cloud.extend(points.iter().cloned());
+
+		let circle = point_cloud.cover_circle();
+		for p in points.iter() {
+			prop_assert!(circle.covers(*p));
+		}
+
+		let best = brute_force_sq_radius(&points);
+		prop_assert!(circle.sq_radius <= best * (1. + 1e-9) + 1e-12);
+	}
+}
+
+proptest! {
+	#[test]
+	fn backends_agree(
+		ops in prop::collection::vec((0_u8..4, -100_f64..100., -100_f64..100.), 1..60)
+	) {
+		use smallest_cover::{Backend, MovingPointCloud};
+
+		let mut dynamic = MovingPointCloud::with_backend(Backend::Dynamic);
+		let mut fifo = MovingPointCloud::with_backend(Backend::Fifo);
+		prop_assert_eq!(fifo.backend(), Backend::Fifo);
+
+		for (op, x, y) in ops.into_iter() {
+			match op {
+				// Evict the oldest point
+				0 => {
+					prop_assert_eq!(dynamic.pop(), fifo.pop());
+				},
+				// Retract some point out of order
+				1 => {
+					let handle = dynamic.iter().map(|(h, _p)| h)
+						.nth((x.abs() as usize) % dynamic.len().max(1));
+					if let Some(handle) = handle {
+						prop_assert_eq!(dynamic.remove(handle), fifo.remove(handle));
+					}
+				},
+				_ => {
+					prop_assert_eq!(dynamic.push(Point{x, y}), fifo.push(Point{x, y}));
+				},
+			}
+
+			prop_assert_eq!(dynamic.len(), fifo.len());
+			if !dynamic.is_empty() {
+				prop_assert_eq!(dynamic.cover_circle(), fifo.cover_circle());
+			}
+		}
+	}
+}
+
+proptest! {
+	#[test]
+	fn invariants_hold(
+		ops in prop::collection::vec((0_u8..4, -4_i8..4, -4_i8..4), 1..60)
+	) {
+		use smallest_cover::MovingPointCloud;
+
+		// Points on a small grid, for plenty of duplicates and collinearities
+		for backend in BACKENDS.iter().cloned() {
+			let mut cloud = MovingPointCloud::with_backend(backend);
+			for (op, x, y) in ops.iter().cloned() {
+				match op {
+					0 => {
+						cloud.pop();
+					},
+					1 => {
+						let handle = cloud.iter().map(|(h, _p)| h)
+							.nth((x.unsigned_abs() as usize) % cloud.len().max(1));
+						if let Some(handle) = handle {
+							cloud.remove(handle);
+						}
+					},
+					_ => {
+						cloud.push(Point{x: x as f64, y: y as f64});
+					},
+				}
+				prop_assert_eq!(cloud.validate(), Ok(()));
+			}
+		}
+	}
+}
+
+// Points drawn to stress the predicates: scattered, on a small grid (so with
+// duplicates), along a line, and on a circle, nudged off it by a few ulps
+fn stress_point() -> impl Strategy<Value = Point> {
+	prop_oneof![
+		(-100_f64..100., -100_f64..100.).prop_map(|(x, y)| Point{x, y}),
+		(-3_i8..3, -3_i8..3).prop_map(|(x, y)| Point{x: x as f64, y: y as f64}),
+		(-100_f64..100.).prop_map(|t| Point{x: t, y: 0.5*t + 1.}),
+		(0_u16..360, -2_i8..3, -2_i8..3).prop_map(|(degrees, dx, dy)| {
+			let theta = (degrees as f64).to_radians();
+			let nudge = |c: f64, ulps: i8| c * (1. + ulps as f64 * f64::EPSILON);
+			Point{x: nudge(10. * theta.cos(), dx), y: nudge(10. * theta.sin(), dy)}
+		}),
+	]
+}
+
+proptest! {
+	#[test]
+	fn window_matches_brute_force(
+		ops in prop::collection::vec((any::<bool>(), stress_point()), 1..80),
+	) {
+		use smallest_circle::circle::ClosedCircle;
+		use smallest_cover::{Backend, MovingPointCloud};
+
+		let mut dynamic = MovingPointCloud::with_backend(Backend::Dynamic);
+		let mut fifo = MovingPointCloud::with_backend(Backend::Fifo);
+		for (push, p) in ops.into_iter() {
+			// Mostly pushes, with the window kept small enough for brute force
+			if push || dynamic.len() > 10 {
+				dynamic.push(p);
+				fifo.push(p);
+			}
+			if !push || dynamic.len() > 10 {
+				prop_assert_eq!(dynamic.pop(), fifo.pop());
+			}
+			prop_assert_eq!(dynamic.validate(), Ok(()));
+			prop_assert_eq!(fifo.validate(), Ok(()));
+
+			let points: Vec<Point> = dynamic.iter().map(|(_h, p)| p).collect();
+			let circle = match dynamic.checked_cover_circle() {
+				Some(circle) => circle,
+				None => continue,
+			};
+			prop_assert_eq!(Some(circle), fifo.checked_cover_circle());
+			prop_assert_eq!(Some(circle), ClosedCircle::covering(&points));
+			prop_assert!(points.iter().all(|p| circle.covers(*p)));
+			let best = brute_force_sq_radius(&points);
+			prop_assert!(circle.sq_radius <= best * (1. + 1e-9) + 1e-12);
+		}
+	}
+}
+
+#[test]
+fn circle_changed() {
+	use smallest_cover::{MovingPointCloud, NonFinitePolicy};
+
+	let mut cloud = MovingPointCloud::new().with_non_finite_policy(NonFinitePolicy::Skip);
+	assert!(!cloud.circle_changed());
+	cloud.push(Point{x: 0., y: 0.});
+	assert!(cloud.circle_changed());
+	cloud.push(Point{x: 2., y: 0.});
+	assert!(cloud.circle_changed());
+	let circle = cloud.cover_circle();
+
+	// Covered, or not a support point
+	let inner = cloud.push(Point{x: 1., y: 0.5});
+	assert!(!cloud.circle_changed());
+	cloud.push(Point{x: 1., y: -1.});
+	assert!(!cloud.circle_changed());
+	cloud.remove(inner);
+	assert!(!cloud.circle_changed());
+	cloud.push(Point{x: f64::NAN, y: 0.});
+	assert!(!cloud.circle_changed());
+	assert_eq!(cloud.cover_circle(), circle);
+
+	// A support point leaves
+	assert_eq!(cloud.pop(), Some(Point{x: 0., y: 0.}));
+	assert!(cloud.circle_changed());
+	assert_eq!(cloud.cover_circle(), point_cloud_of(&[Point{x: 2., y: 0.}, Point{x: 1., y: -1.}]).cover_circle());
+	cloud.pop();
+	cloud.pop();
+	assert!(cloud.circle_changed());
+	assert_eq!(cloud.checked_cover_circle(), None);
+}
+
+#[test]
+fn circle_change_observer() {
+	use smallest_circle::circle::ClosedCircle;
+	use smallest_cover::MovingPointCloud;
+	use std::sync::mpsc;
+
+	let (sender, changes) = mpsc::channel();
+	let mut cloud = MovingPointCloud::new().with_observer(move |change| sender.send(change.clone()).unwrap());
+	let p = |x, y| Point{x, y};
+
+	cloud.push(p(0., 0.));
+	let change = changes.try_recv().unwrap();
+	assert_eq!(change.old_circle, None);
+	assert_eq!(change.new_circle, Some(ClosedCircle::from_point(p(0., 0.))));
+	assert_eq!((change.old_support, change.new_support), (vec![], vec![p(0., 0.)]));
+	assert_eq!((change.hull_added, change.hull_removed), (vec![p(0., 0.)], vec![]));
+
+	cloud.push(p(2., 0.));
+	let change = changes.try_recv().unwrap();
+	assert_eq!(change.new_circle, Some(ClosedCircle::new(p(1., 0.), 1.)));
+	assert_eq!(change.new_support, vec![p(0., 0.), p(2., 0.)]);
+	assert_eq!(change.hull_added, vec![p(2., 0.)]);
+
+	// Covered points leave the circle be, though not the hull; one on the
+	// circle joins the support
+	cloud.push(p(1., 0.5));
+	let change = changes.try_recv().unwrap();
+	assert_eq!(change.new_circle, change.old_circle);
+	assert_eq!((change.hull_added, change.hull_removed), (vec![p(1., 0.5)], vec![]));
+	cloud.push(p(1., -1.));
+	let change = changes.try_recv().unwrap();
+	assert_eq!(change.new_circle, Some(ClosedCircle::new(p(1., 0.), 1.)));
+	assert_eq!(change.new_support, vec![p(0., 0.), p(1., -1.), p(2., 0.)]);
+	assert_eq!((change.hull_added, change.hull_removed), (vec![p(1., -1.)], vec![]));
+	cloud.push(p(1., 0.));
+	cloud.push(p(1., -1.));
+	assert!(changes.try_recv().is_err());
+
+	cloud.push(p(4., 0.));
+	let change = changes.try_recv().unwrap();
+	assert_eq!(change.old_circle, Some(ClosedCircle::new(p(1., 0.), 1.)));
+	assert_eq!(change.new_circle, Some(ClosedCircle::new(p(2., 0.), 2.)));
+	assert_eq!(change.old_support, vec![p(0., 0.), p(1., -1.), p(2., 0.)]);
+	assert_eq!(change.new_support, vec![p(0., 0.), p(4., 0.)]);
+	assert_eq!((change.hull_added, change.hull_removed), (vec![p(4., 0.)], vec![p(2., 0.)]));
+
+	// Each removal of a support point is an update of its own
+	cloud.pop();
+	let change = changes.try_recv().unwrap();
+	assert_eq!(change.new_circle, cloud.checked_cover_circle());
+	assert_eq!(change.hull_removed, vec![p(0., 0.)]);
+	while cloud.pop().is_some() {}
+	let last = changes.try_iter().last().unwrap();
+	assert_eq!(last.new_circle, None);
+	assert_eq!(last.new_support, vec![]);
+
+	// An observer leaves clouds shareable across threads
+	fn is_sync<T: Sync>() {}
+	is_sync::<MovingPointCloud>();
+	is_sync::<smallest_circle::window::TimedPointCloud<f64>>();
+}
+
+#[test]
+fn supported_cover_circle() {
+	use smallest_circle::circle::ClosedCircle;
+	use smallest_cover::MovingPointCloud;
+
+	let p = |x, y| Point{x, y};
+	for backend in BACKENDS.iter() {
+		let mut cloud = MovingPointCloud::with_backend(*backend);
+		assert_eq!(cloud.supported_cover_circle(), Ok(None));
+
+		let first = cloud.push(p(0., 0.));
+		let supported = cloud.supported_cover_circle().unwrap().unwrap();
+		assert_eq!(supported.support, vec![(first, p(0., 0.))]);
+
+		// Right angle at (0, 0), so the hypotenuse is a diameter; all three
+		// corners lie on the circle, and make up the support
+		let right = cloud.push(p(4., 0.));
+		cloud.push(p(1., 1.));
+		let top = cloud.push(p(0., 3.));
+		let supported = cloud.supported_cover_circle().unwrap().unwrap();
+		assert_eq!(supported.circle, ClosedCircle::new(p(2., 1.5), 2.5));
+		assert_eq!(supported.support, vec![(first, p(0., 0.)), (top, p(0., 3.)), (right, p(4., 0.))]);
+
+		// Acute, so all three points are needed; the newest of two
+		// coincident points stands for both
+		let newest = cloud.push(p(0., 0.));
+		cloud.push(p(3., 4.5));
+		let supported = cloud.supported_cover_circle().unwrap().unwrap();
+		assert_eq!(supported.circle, cloud.cover_circle());
+		assert_eq!(supported.support.len(), 3);
+		assert_eq!(supported.support[0], (newest, p(0., 0.)));
+		assert!(supported.support.iter().all(|(handle, s)| cloud.get(*handle) == Some(*s)));
+		cloud.remove(first);
+		assert_eq!(cloud.supported_cover_circle(), Ok(Some(supported)));
+	}
+}
+
+#[test]
+fn cover_circle_with_outliers() {
+	use smallest_circle::circle::ClosedCircle;
+	use smallest_cover::MovingPointCloud;
+
+	for backend in BACKENDS.iter() {
+		let mut cloud = MovingPointCloud::with_backend(*backend);
+		assert_eq!(cloud.cover_circle_with_outliers(1), None);
+
+		// Readings about the origin, with one spurious reading far off
+		cloud.extend((0..12).map(|i| Point{x: (i as f64).cos(), y: (i as f64).sin()}));
+		let spurious = cloud.push(Point{x: 50., y: 20.});
+		cloud.extend((0..4).map(|i| Point{x: 0.1 * i as f64, y: 0.}));
+		assert!(cloud.cover_circle().radius() > 20.);
+
+		let unchanged = cloud.cover_circle_with_outliers(0).unwrap();
+		assert_eq!(unchanged.circle, cloud.cover_circle());
+		assert!(unchanged.outliers.is_empty());
+
+		let robust = cloud.cover_circle_with_outliers(1).unwrap();
+		assert!(robust.exact);
+		assert_eq!(robust.outliers, vec![(spurious, Point{x: 50., y: 20.})]);
+		let kept: Vec<Point> = cloud.iter().filter(|(h, _p)| *h != spurious).map(|(_h, p)| p).collect();
+		assert_eq!(Some(robust.circle), ClosedCircle::covering(&kept));
+
+		// Leaving out more only shrinks the circle
+		let mut sq_radius = robust.circle.sq_radius;
+		for k in 2..8 {
+			let circle = cloud.cover_circle_with_outliers(k).unwrap();
+			assert!(circle.outliers.len() <= k);
+			assert!(circle.circle.sq_radius <= sq_radius);
+			assert_eq!(circle.exact, k <= 4);
+			assert!(cloud.iter().all(|p| circle.outliers.contains(&p) || circle.circle.covers(p.1)));
+			sq_radius = circle.circle.sq_radius;
+		}
+
+		// With every position doubled, no single point can be left out, and
+		// the circle is the cover circle, bit for bit
+		let points: Vec<Point> = cloud.iter().map(|(_h, p)| p).collect();
+		cloud.extend(points.iter().cloned());
+		let doubled = cloud.cover_circle_with_outliers(1).unwrap();
+		assert_eq!(doubled.circle, cloud.cover_circle());
+		assert!(doubled.outliers.is_empty());
+	}
+}
+
+#[test]
+fn geometry_api() {
+	use smallest_circle::circle::ClosedCircle;
+	use smallest_circle::polygon::ConvexPolygon;
+
+	let points = [
+		Point{x: 0., y: 0.},
+		Point{x: 2., y: 0.},
+		Point{x: 1., y: 0.5},
+		Point{x: 0., y: 2.},
+	];
+	let polygon: ConvexPolygon = points.iter().cloned().collect();
+	assert_eq!(polygon.degree(), 3);
+	assert_eq!(polygon.edges().count(), 3);
+
+	let circle: ClosedCircle = point_cloud_of(&points).cover_circle();
+	assert_eq!(Some(circle), ClosedCircle::covering(polygon.positions()));
+	assert!(polygon.positions().iter().all(|p| circle.covers(*p)));
+}
+
+#[test]
+fn fallible_operations() {
+	use smallest_circle::error::Error;
+
+	let mut point_cloud = smallest_cover::MovingPointCloud::new();
+	assert_eq!(point_cloud.try_pop(), Err(Error::EmptyCloud));
+	assert_eq!(point_cloud.try_cover_circle(), Err(Error::EmptyCloud));
+
+	// A bad reading is reported, and leaves the cloud as it was
+	let a = Point{x: 1., y: 0.};
+	let handle = point_cloud.try_push(a).unwrap();
+	let bad = Point{x: 0., y: f64::INFINITY};
+	assert_eq!(point_cloud.try_push(bad), Err(Error::NonFinitePoint(bad)));
+	assert!(point_cloud.try_push(Point{x: f64::NAN, y: 0.}).is_err());
+	assert_eq!(point_cloud.len(), 1);
+	assert_eq!(point_cloud.iter().collect::<Vec<_>>(), vec!((handle, a)));
+	assert_eq!(point_cloud.try_cover_circle(), Ok(point_cloud_of(&[a]).cover_circle()));
+
+	assert_eq!(point_cloud.try_pop(), Ok(a));
+	assert_eq!(point_cloud.try_pop(), Err(Error::EmptyCloud));
+	assert_eq!(Error::EmptyCloud.to_string(), "point cloud is empty");
+
+	// Unknown handles, including those of points already gone
+	let b = point_cloud.try_push(a).unwrap();
+	assert_eq!(point_cloud.try_remove(handle), Err(Error::UnknownHandle(handle)));
+	assert_eq!(point_cloud.try_remove(b), Ok(a));
+	assert_eq!(point_cloud.try_remove(b), Err(Error::UnknownHandle(b)));
+	assert!(point_cloud.is_empty());
+}
+
+#[test]
+fn non_finite_policy() {
+	use smallest_cover::{MovingPointCloud, NonFinitePolicy};
+
+	let a = Point{x: 1., y: 0.};
+	let b = Point{x: -1., y: 0.};
+	let inf = Point{x: f64::INFINITY, y: -1.};
+	let nan = Point{x: 0., y: f64::NAN};
+
+	let mut rejecting = MovingPointCloud::new();
+	assert_eq!(rejecting.non_finite_policy(), NonFinitePolicy::Reject);
+	assert!(rejecting.try_push(inf).is_err());
+	assert_eq!(rejecting.checked_cover_circle(), None);
+
+	let mut skipping = MovingPointCloud::new()
+		.with_non_finite_policy(NonFinitePolicy::Skip);
+	skipping.push(a);
+	let handle = skipping.push(inf);
+	skipping.push(nan);
+	skipping.push(b);
+	assert_eq!(skipping.len(), 2);
+	assert_eq!(skipping.skipped(), 2);
+	assert_eq!(skipping.get(handle), None);
+	assert_eq!(skipping.checked_cover_circle(), Some(point_cloud_of(&[a, b]).cover_circle()));
+
+	let mut clamping = MovingPointCloud::new()
+		.with_non_finite_policy(NonFinitePolicy::Clamp(10.));
+	clamping.push(a);
+	let handle = clamping.push(inf);
+	clamping.push(nan);
+	assert_eq!(clamping.len(), 2);
+	assert_eq!(clamping.skipped(), 1);
+	assert_eq!(clamping.get(handle), Some(Point{x: 10., y: -1.}));
+}
+
+#[test]
+fn empty_cover_circle() {
+	use smallest_circle::circle::ClosedCircle;
+
+	let circle = smallest_cover::MovingPointCloud::new().cover_circle();
+	assert!(circle.center.x.is_nan() && circle.center.y.is_nan());
+	assert_eq!(circle.sq_radius, 0.);
+
+	let circle = smallest_cover::MovingPointCloud::<i32>::default().cover_circle();
+	assert_eq!(circle, ClosedCircle{center: Point{x: 0, y: 0}, sq_radius: 0});
+	// The window does not carry the sentinel over
+	let window = smallest_circle::window::TimedPointCloud::<u64>::new();
+	assert_eq!(window.cover_circle(), None);
+}
+
+#[test]
+fn generic_scalars() {
+	use smallest_circle::circle::ClosedCircle;
+	use smallest_cover::{Backend, MovingPointCloud};
+
+	let coords = [(0, 0), (8, 0), (4, 3), (1, 6), (5, -2)];
+
+	// Single precision agrees with double, up to rounding
+	let mut single = MovingPointCloud::<f32>::default();
+	single.extend(coords.iter().map(|(x, y)| Point{x: *x as f32, y: *y as f32}));
+	let double = point_cloud_of(&coords.iter()
+		.map(|(x, y)| Point{x: *x as f64, y: *y as f64})
+		.collect::<Vec<_>>());
+	assert!((single.cover_circle().radius() - double.cover_circle().radius()).abs() < 1e-6);
+
+	// Integer circles are computed in f64, which here cannot resolve units
+	// (its ulp is 16), so they are larger than they need be; but they still
+	// cover every point exactly
+	let mut integer = MovingPointCloud::<i64>::with_backend(Backend::Fifo);
+	let offset = 1_i64 << 56;
+	integer.extend(coords.iter().map(|(x, y)| Point{x: offset + x, y: offset - y}));
+	let circle = integer.cover_circle();
+	assert!(integer.iter().all(|(_h, p)| circle.covers(p)));
+	assert!(circle.radius() >= double.cover_circle().radius());
+	assert!(circle.radius() <= double.cover_circle().radius() + 16.);
+
+	let points: Vec<Point<i32>> = coords.iter().map(|(x, y)| Point{x: *x as i32, y: *y as i32}).collect();
+	let circle = ClosedCircle::covering(&points).unwrap();
+	assert!(points.iter().all(|p| circle.covers(*p)));
+	assert_eq!(integer.pop(), Some(Point{x: offset, y: offset}));
+
+	// Points too far apart to square their distance in an i32, on a circle
+	// whose squared radius fits one
+	let mut integer = MovingPointCloud::<i32>::default();
+	integer.extend([(-30000, 0), (0, 0), (30000, 0), (0, 30000)].iter().map(|(x, y)| Point{x: *x, y: *y}));
+	assert_eq!(integer.cover_circle(), ClosedCircle{center: Point{x: 0, y: 0}, sq_radius: 900_000_000});
+	// And one whose squared radius does not
+	integer.push(Point{x: 0, y: -100000});
+	assert_eq!(integer.try_cover_circle(), Err(smallest_circle::error::Error::RadiusOverflow));
+	assert_eq!(ClosedCircle::covering(&integer.iter().map(|(_h, p)| p).collect::<Vec<_>>()), None);
+	assert_eq!(integer.validate(), Ok(()));
+}
+
+#[cfg(feature = "exact")]
+proptest! {
+	#[test]
+	fn float_path_is_certified(
+		coords in prop::collection::vec((-1000_i32..1000, -1000_i32..1000), 1..40)
+	) {
+		use smallest_circle::exact::{ExactCircle, ExactPointCloud};
+		use num_rational::BigRational;
+
+		let mut exact = ExactPointCloud::default();
+		exact.extend(coords.iter().map(|(x, y)| Point{x: *x, y: *y}));
+		let exact_circle = exact.exact_cover_circle().unwrap();
+
+		let points: Vec<Point> = coords.iter()
+			.map(|(x, y)| Point{x: *x as f64, y: *y as f64})
+			.collect();
+		let circle = point_cloud_of(&points).cover_circle();
+
+		// No covering circle is smaller than the exact one, and the float
+		// circle covers every point; so it may only be larger, and barely
+		let sq_radius = BigRational::from_float(circle.sq_radius).unwrap();
+		prop_assert!(exact_circle.sq_radius <= sq_radius);
+		prop_assert!(circle.radius() - exact_circle.radius() <= 1e-9 * circle.radius().max(1.));
+		prop_assert_eq!(ExactCircle::covering(&exact.iter().map(|(_h, p)| p).collect::<Vec<_>>()), Some(exact_circle));
+	}
+}
+
+proptest! {
+	#[test]
+	fn sphere_window(
+		coords in prop::collection::vec((-100_f64..100., -100_f64..100., -100_f64..100.), 1..40)
+	) {
+		use smallest_circle::points::Point3;
+		use smallest_circle::sphere::ClosedSphere;
+		use smallest_cover::MovingPointCloud3;
+
+		let mut window = MovingPointCloud3::new();
+		for (i, (x, y, z)) in coords.iter().cloned().enumerate() {
+			window.push(Point3{x, y, z});
+			if window.len() > 8 {
+				window.pop();
+			}
+
+			let points: Vec<Point3> = window.iter().map(|(_h, p)| p).collect();
+			let sphere = window.cover_sphere();
+			prop_assert!(points.iter().all(|p| sphere.covers(*p)));
+
+			// No sphere through up to four of the points covers them all and
+			// is smaller, allowing for rounding
+			let covers_all = |s: &ClosedSphere| points.iter()
+				.all(|p| (*p - s.center).sq_mag() <= s.sq_radius * (1. + 1e-9) + 1e-9);
+			let n = points.len();
+			for i in 0..n {
+				for j in i..n {
+					for k in j..n {
+						for l in k..n {
+							let s = ClosedSphere::from_four_points_or_fewer(points[i], points[j], points[k], points[l]);
+							if covers_all(&s) {
+								prop_assert!(sphere.sq_radius <= s.sq_radius * (1. + 1e-9) + 1e-9);
+							}
+						}
+					}
+				}
+			}
+
+			// No point pair is farther apart than the diameter
+			for p1 in points.iter() {
+				for p2 in points.iter() {
+					prop_assert!((*p2-*p1).sq_mag() <= 4. * sphere.sq_radius * (1. + 1e-9));
+				}
+			}
+			prop_assert_eq!(window.len(), (i+1).min(8));
+		}
+	}
+
+	#[test]
+	fn planar_sphere_is_circle(
+		coords in prop::collection::vec((-100_f64..100., -100_f64..100.), 1..12)
+	) {
+		use smallest_circle::points::Point3;
+		use smallest_circle::sphere::ClosedSphere;
+
+		let points: Vec<Point> = coords.iter().map(|(x, y)| Point{x: *x, y: *y}).collect();
+		let points3: Vec<Point3> = coords.iter().map(|(x, y)| Point3{x: *x, y: *y, z: 0.}).collect();
+		let circle = point_cloud_of(&points).cover_circle();
+		let sphere = ClosedSphere::covering(&points3).unwrap();
+		prop_assert!((sphere.radius() - circle.radius()).abs() <= 1e-9 * circle.radius().max(1.));
+	}
+}
+
+proptest! {
+	#[test]
+	fn ball_agrees_with_circle(
+		coords in prop::collection::vec((-100_f64..100., -100_f64..100.), 1..40)
+	) {
+		use smallest_circle::ball::{ClosedBall, PointN};
+
+		let points: Vec<Point> = coords.iter().map(|(x, y)| Point{x: *x, y: *y}).collect();
+		let circle = point_cloud_of(&points).cover_circle();
+		let ball = ClosedBall::covering(&points.iter().map(|p| PointN::from(*p)).collect::<Vec<_>>()).unwrap();
+		prop_assert_eq!(Point::from(ball.center).to_bits(), circle.center.to_bits());
+		prop_assert_eq!(ball.sq_radius.to_bits(), circle.sq_radius.to_bits());
+	}
+
+	#[test]
+	fn ball_agrees_with_sphere(
+		coords in prop::collection::vec((-100_f64..100., -100_f64..100., -100_f64..100.), 1..40)
+	) {
+		use smallest_circle::ball::{ClosedBall, PointN};
+		use smallest_circle::points::Point3;
+		use smallest_circle::sphere::ClosedSphere;
+
+		let points: Vec<Point3> = coords.iter().map(|(x, y, z)| Point3{x: *x, y: *y, z: *z}).collect();
+		let sphere = ClosedSphere::covering(&points).unwrap();
+		let points: Vec<PointN<3>> = points.into_iter().map(PointN::from).collect();
+		let ball = ClosedBall::covering(&points).unwrap();
+		prop_assert!(points.iter().all(|p| ball.covers(p)));
+		prop_assert!((ball.radius() - sphere.radius()).abs() <= 1e-9 * sphere.radius().max(1.));
+	}
+}
+
+#[test]
+fn snapshot_and_restore() {
+	use smallest_circle::error::Error;
+	use smallest_circle::polygon::ConvexPolygon;
+	use smallest_cover::{MovingPointCloud, NonFinitePolicy};
+
+	for backend in BACKENDS.iter().cloned() {
+		let mut cloud = MovingPointCloud::with_backend(backend)
+			.with_non_finite_policy(NonFinitePolicy::Skip);
+		let handles: Vec<_> = (0..30).map(|i| cloud.push(wavy_point(i))).collect();
+		cloud.push(Point{x: f64::NAN, y: 0.});
+		cloud.pop();
+		cloud.remove(handles[10]);
+
+		let snapshot = cloud.snapshot();
+		assert_eq!(snapshot.points.len(), 28);
+		assert_eq!(snapshot.hull.len(), ConvexPolygon::try_from_positions(snapshot.hull.clone()).unwrap().degree());
+		assert_eq!(snapshot.hull.len(), cloud.hull_len());
+		let mut restored = MovingPointCloud::from_snapshot(snapshot.clone()).unwrap();
+		assert_eq!(restored.snapshot(), snapshot);
+		for i in 30..60 {
+			assert_eq!(restored.push(wavy_point(i)), cloud.push(wavy_point(i)));
+			assert_eq!(restored.pop(), cloud.pop());
+			assert_eq!(restored.cover_circle(), cloud.cover_circle());
+		}
+	}
+
+	let mut cloud = MovingPointCloud::new();
+	cloud.extend((0..20).map(wavy_point));
+	let snapshot = cloud.snapshot();
+	let restore = |edit: &dyn Fn(&mut smallest_cover::CloudSnapshot)| {
+		let mut snapshot = snapshot.clone();
+		edit(&mut snapshot);
+		MovingPointCloud::from_snapshot(snapshot).map(|_cloud| ())
+	};
+	let invalid = |what| Err(Error::InvalidSnapshot(what));
+
+	assert_eq!(restore(&|_s| ()), Ok(()));
+	assert_eq!(restore(&|s| s.points.swap(3, 4)), invalid("point handles are not in increasing order"));
+	assert_eq!(restore(&|s| s.next_handle = s.points[19].0), invalid("point handle is not older than the next handle"));
+	assert_eq!(restore(&|s| s.skipped = 1), invalid("more points logged and skipped than handles given out"));
+	assert_eq!(restore(&|s| s.points[5].1.y = f64::NAN), invalid("logged point has a non-finite coordinate"));
+	assert_eq!(restore(&|s| s.non_finite_policy = NonFinitePolicy::Clamp(f64::INFINITY)), invalid("clamp limit is not finite"));
+	assert_eq!(restore(&|s| s.hull.reverse()), invalid("hull is not convex and counter-clockwise"));
+	assert_eq!(restore(&|s| s.hull.iter_mut().for_each(|v| *v = Point{x: 2.*v.x, y: 2.*v.y})), invalid("hull vertex is not a logged point"));
+	assert_eq!(restore(&|s| { s.hull.pop(); }), invalid("logged point lies outside the hull"));
+	assert_eq!(restore(&|s| s.hull.clear()), invalid("logged point lies outside the hull"));
+	assert_eq!(
+		Error::InvalidSnapshot("hull vertex is not a logged point").to_string(),
+		"invalid snapshot: hull vertex is not a logged point",
+	);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn checkpoint_and_restore() {
+	use smallest_cover::{MovingPointCloud, NonFinitePolicy};
+	use smallest_circle::window::TimedPointCloud;
+
+	for backend in BACKENDS.iter().cloned() {
+		let mut cloud = MovingPointCloud::with_backend(backend)
+			.with_non_finite_policy(NonFinitePolicy::Skip);
+		let handles: Vec<_> = (0..40).map(|i| cloud.push(wavy_point(i))).collect();
+		cloud.push(wavy_point(3));
+		cloud.push(Point{x: f64::NAN, y: 0.});
+		for _ in 0..5 {
+			cloud.pop();
+		}
+		cloud.remove(handles[20]);
+
+		let json = serde_json::to_string(&cloud).unwrap();
+		let mut restored: MovingPointCloud = serde_json::from_str(&json).unwrap();
+		assert_eq!(restored.backend(), backend);
+		assert_eq!(restored.non_finite_policy(), NonFinitePolicy::Skip);
+		assert_eq!(restored.skipped(), 1);
+		assert_eq!(restored.iter().collect::<Vec<_>>(), cloud.iter().collect::<Vec<_>>());
+		assert_eq!(restored.cover_circle(), cloud.cover_circle());
+
+		// The restored cloud carries on as the original does
+		for i in 40..80 {
+			assert_eq!(restored.push(wavy_point(i)), cloud.push(wavy_point(i)));
+			assert_eq!(restored.pop(), cloud.pop());
+			assert_eq!(restored.cover_circle(), cloud.cover_circle());
+		}
+	}
+
+	let mut window = TimedPointCloud::<u64>::new().with_max_age(10);
+	window.extend((0..30).map(|i| (i, wavy_point(i))));
+	let json = serde_json::to_string(&window).unwrap();
+	let mut restored: TimedPointCloud<u64> = serde_json::from_str(&json).unwrap();
+	assert_eq!(restored.oldest(), window.oldest());
+	assert_eq!(restored.cover_circle(), window.cover_circle());
+	restored.push(45, wavy_point(45));
+	assert_eq!(restored.len(), 1);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn checkpoint_is_validated() {
+	use smallest_cover::MovingPointCloud;
+	use smallest_circle::window::TimedPointCloud;
+
+	let mut cloud = MovingPointCloud::new();
+	cloud.extend((0..20).map(wavy_point));
+	let checkpoint = serde_json::to_value(&cloud).unwrap();
+	assert!(serde_json::from_value::<MovingPointCloud>(checkpoint.clone()).is_ok());
+
+	// A hull that leaves out logged points, or points missing from the hull
+	let mut corrupted = checkpoint.clone();
+	corrupted["hull"] = serde_json::json!([checkpoint["points"][0][1]]);
+	let error = serde_json::from_value::<MovingPointCloud>(corrupted).err().unwrap();
+	assert_eq!(error.to_string(), "invalid snapshot: logged point lies outside the hull");
+	let mut corrupted = checkpoint;
+	corrupted["points"].as_array_mut().unwrap().push(serde_json::json!([25, {"x": 100., "y": 0.}]));
+	assert!(serde_json::from_value::<MovingPointCloud>(corrupted).is_err());
+
+	// A window is checked too, beyond its cloud
+	let mut window = TimedPointCloud::<u64>::new().with_max_age(10);
+	window.extend((0..20).map(|i| (i, wavy_point(i))));
+	let checkpoint = serde_json::to_value(&window).unwrap();
+	assert!(serde_json::from_value::<TimedPointCloud<u64>>(checkpoint.clone()).is_ok());
+	let restore = |edit: &dyn Fn(&mut serde_json::Value)| {
+		let mut corrupted = checkpoint.clone();
+		edit(&mut corrupted);
+		serde_json::from_value::<TimedPointCloud<u64>>(corrupted).map_err(|e| e.to_string())
+	};
+	let invalid = |what| Some(format!("invalid snapshot: {}", what));
+	assert_eq!(
+		restore(&|c| { c["timestamps"].as_array_mut().unwrap().pop(); }).err(),
+		invalid("window holds a different number of timestamps and points"),
+	);
+	assert_eq!(
+		restore(&|c| c["timestamps"].as_array_mut().unwrap().swap(0, 1)).err(),
+		invalid("timestamps are not in increasing order"),
+	);
+	assert_eq!(
+		restore(&|c| c["max_count"] = serde_json::json!(3)).err(),
+		invalid("window holds more points than its maximum count"),
+	);
+	assert_eq!(
+		restore(&|c| c["max_age"] = serde_json::json!(2)).err(),
+		invalid("window holds points older than its maximum age"),
+	);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn checkpoint_sphere_cloud() {
+	use smallest_circle::points::Point3;
+	use smallest_cover::MovingPointCloud3;
+
+	let mut cloud = MovingPointCloud3::new();
+	cloud.extend((0..30).map(|i| Point3{x: (i % 7) as f64, y: (i % 5) as f64, z: (i % 3) as f64}));
+	cloud.pop();
+	let json = serde_json::to_string(&cloud).unwrap();
+	let mut restored: MovingPointCloud3 = serde_json::from_str(&json).unwrap();
+	assert_eq!(restored.iter().collect::<Vec<_>>(), cloud.iter().collect::<Vec<_>>());
+	assert_eq!(restored.cover_sphere().radius(), cloud.cover_sphere().radius());
+	assert_eq!(restored.push(Point3{x: 0., y: 0., z: 0.}), cloud.push(Point3{x: 0., y: 0., z: 0.}));
+
+	// Handles must all have been given out
+	let json = json.replace("\"next_handle\":30", "\"next_handle\":3");
+	assert!(serde_json::from_str::<MovingPointCloud3>(&json).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_geometry() {
+	use smallest_circle::ball::{ClosedBall, PointN};
+	use smallest_circle::circle::ClosedCircle;
+	use smallest_circle::polygon::ConvexPolygon;
+
+	let circle = ClosedCircle{center: Point{x: 1.5, y: -2.}, sq_radius: 4.};
+	let json = serde_json::to_string(&circle).unwrap();
+	assert_eq!(json, r#"{"center":{"x":1.5,"y":-2.0},"sq_radius":4.0}"#);
+	assert_eq!(serde_json::from_str::<ClosedCircle>(&json).unwrap(), circle);
+
+	let ball = ClosedBall{center: PointN([1., 2., 3.]), sq_radius: 1.};
+	let json = serde_json::to_string(&ball).unwrap();
+	assert_eq!(json, r#"{"center":[1.0,2.0,3.0],"sq_radius":1.0}"#);
+	assert_eq!(serde_json::from_str::<ClosedBall<3>>(&json).unwrap(), ball);
+	assert!(serde_json::from_str::<PointN<3>>("[1.0,2.0]").is_err());
+
+	let polygon = ConvexPolygon::hull_of(&[
+		Point{x: 0., y: 0.}, Point{x: 2., y: 0.}, Point{x: 1., y: 1.}, Point{x: 0., y: 2.},
+	]);
+	let json = serde_json::to_string(&polygon).unwrap();
+	assert_eq!(json, r#"[{"x":0.0,"y":0.0},{"x":2.0,"y":0.0},{"x":0.0,"y":2.0}]"#);
+	assert_eq!(serde_json::from_str::<ConvexPolygon>(&json).unwrap(), polygon);
+	// Clockwise, and non-convex, vertex lists are rejected
+	let clockwise = r#"[{"x":0.0,"y":0.0},{"x":0.0,"y":2.0},{"x":2.0,"y":0.0}]"#;
+	assert!(serde_json::from_str::<ConvexPolygon>(clockwise).is_err());
+	let dented = r#"[{"x":0.0,"y":0.0},{"x":2.0,"y":0.0},{"x":0.5,"y":0.5},{"x":0.0,"y":2.0}]"#;
+	assert!(serde_json::from_str::<ConvexPolygon>(dented).is_err());
+}